@@ -0,0 +1,80 @@
+//! Decisions of `build.rs` which only depend on its environment, shared with their tests in
+//! `tests/build_platform.rs`.
+//!
+//! The selection of the C++ standard library only depends on the `CARGO_CFG_TARGET_*` values
+//! of the target and on the kind of compiler, never on the host, so that cross-compiling, e.g.
+//! to illumos from a Linux CI runner, selects the same library as a native build.
+
+/// A C++ standard library which Ada can be compiled against and linked with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CppStdlib {
+    /// LLVM's libc++, the system library of Apple platforms, FreeBSD and OpenBSD.
+    LibCpp,
+    /// GCC's libstdc++, the system library of Linux, NetBSD, DragonFly BSD and illumos.
+    LibStdCpp,
+    /// The library of the MSVC toolchain, which its linker finds on its own.
+    Msvc,
+}
+
+impl CppStdlib {
+    /// Returns the library for a target, given its `CARGO_CFG_TARGET_OS`, `CARGO_CFG_TARGET_ENV`
+    /// and `CARGO_CFG_TARGET_VENDOR`, whether the compiler is GCC, and whether the `libcpp`
+    /// Cargo feature is enabled.
+    ///
+    /// GCC always uses its own libstdc++, e.g. `g++` installed from the ports of FreeBSD, whose
+    /// base system uses libc++. Clang uses the library of the system, or libc++ with `libcpp`.
+    pub fn for_target(os: &str, env: &str, vendor: &str, is_gcc: bool, libcpp: bool) -> Self {
+        if env == "msvc" {
+            return Self::Msvc;
+        }
+        if is_gcc {
+            return Self::LibStdCpp;
+        }
+        match os {
+            _ if vendor == "apple" => Self::LibCpp,
+            "android" | "freebsd" | "openbsd" | "wasi" => Self::LibCpp,
+            // NetBSD, DragonFly BSD and illumos ship GCC, and Clang on them defaults to its
+            // libstdc++.
+            _ if libcpp => Self::LibCpp,
+            _ => Self::LibStdCpp,
+        }
+    }
+
+    /// Returns the name of the library to link, or `None` if the linker finds it on its own.
+    ///
+    /// Ada does not call `backtrace(3)`, so unlike other C++ code on the BSDs, it never needs
+    /// `-lexecinfo`.
+    pub fn link_name(self) -> Option<&'static str> {
+        match self {
+            Self::LibCpp => Some("c++"),
+            Self::LibStdCpp => Some("stdc++"),
+            Self::Msvc => None,
+        }
+    }
+}
+
+/// Returns whether `CARGO_ENCODED_RUSTFLAGS` enable `-Clinker-plugin-lto`, in any of its forms:
+/// `-Clinker-plugin-lto`, `-C linker-plugin-lto`, `--codegen linker-plugin-lto`, or with a value
+/// such as `=yes` or the path of the plugin. The last occurrence wins, so `=no` disables it.
+pub fn linker_plugin_lto(encoded_rustflags: &str) -> bool {
+    let mut enabled = false;
+    let mut previous = "";
+    for flag in encoded_rustflags.split('\x1f') {
+        let option = match flag.strip_prefix("-C").or(flag.strip_prefix("--codegen=")) {
+            Some(option) if !option.is_empty() => option,
+            _ if matches!(previous, "-C" | "--codegen") => flag,
+            _ => "",
+        };
+        previous = flag;
+        match option.strip_prefix("linker-plugin-lto") {
+            Some("") => enabled = true,
+            Some(value) => {
+                if let Some(value) = value.strip_prefix('=') {
+                    enabled = !matches!(value, "n" | "no" | "off" | "false");
+                }
+            }
+            None => {}
+        }
+    }
+    enabled
+}
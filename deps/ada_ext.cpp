@@ -0,0 +1,584 @@
+// Additional C entry points used by the Rust bindings which are not (yet)
+// part of Ada's C API. They follow the conventions of `ada_c.cpp`: an
+// `ada_url` is a pointer to an `ada::result<ada::url_aggregator>`.
+
+#include "ada.h"
+
+extern "C" {
+#include "ada_c.h"
+
+// UTS #46 processing options, see ada_idna_to_ascii_with_options.
+typedef struct {
+  bool be_strict;
+  bool check_hyphens;
+  bool check_bidi;
+  bool verify_dns_length;
+  bool transitional;
+} ada_idna_options;
+}
+
+namespace {
+
+ada::result<ada::url_aggregator>& get_url(ada_url result) noexcept {
+  return *(ada::result<ada::url_aggregator>*)result;
+}
+
+ada_string make_string(std::string_view value) noexcept {
+  ada_string out{};
+  out.data = value.data();
+  out.length = value.length();
+  return out;
+}
+
+ada_owned_string make_owned_string(std::string_view value) noexcept {
+  ada_owned_string owned{};
+  owned.length = value.size();
+  owned.data = new char[owned.length];
+  memcpy((void*)owned.data, value.data(), owned.length);
+  return owned;
+}
+
+// ada::idna::is_ascii is constexpr and only defined in ada.cpp.
+bool is_ascii(std::u32string_view view) noexcept {
+  return std::all_of(view.begin(), view.end(),
+                     [](char32_t c) { return c < 0x80; });
+}
+
+void append_utf8(std::string& out, std::u32string_view value) {
+  size_t utf8_length =
+      ada::idna::utf8_length_from_utf32(value.data(), value.size());
+  size_t start = out.size();
+  out.resize(start + utf8_length);
+  ada::idna::utf32_to_utf8(value.data(), value.size(), out.data() + start);
+}
+
+// Replaces the deviation characters as required by transitional processing.
+std::u32string apply_transitional_mapping(std::u32string_view input) {
+  std::u32string out;
+  out.reserve(input.size());
+  for (char32_t c : input) {
+    if (c == 0xdf) {
+      out.append(U"ss");
+    } else if (c == 0x3c2) {
+      out.push_back(0x3c3);
+    } else if (c != 0x200c && c != 0x200d) {
+      out.push_back(c);
+    }
+  }
+  return out;
+}
+
+bool is_label_valid(std::u32string_view label,
+                    const ada_idna_options& options) {
+  if (options.check_hyphens) {
+    if (label.front() == '-' || label.back() == '-') {
+      return false;
+    }
+    if (label.size() >= 4 && label[2] == '-' && label[3] == '-') {
+      return false;
+    }
+  }
+  if (options.be_strict) {
+    for (char32_t c : label) {
+      if (c < 0x80 && !((c >= 'a' && c <= 'z') || (c >= '0' && c <= '9') ||
+                        c == '-')) {
+        return false;
+      }
+    }
+  }
+  // Ada's validation always applies CheckJoiners, and applies CheckBidi
+  // unless the label contains a joiner. Validating the first code point on
+  // its own only checks that the label does not begin with a combining mark.
+  if (options.check_bidi ||
+      label.find_first_of(U"\u200c\u200d") != std::u32string_view::npos) {
+    return ada::idna::is_label_valid(label);
+  }
+  return ada::idna::is_label_valid(label.substr(0, 1));
+}
+
+// Ada's IPv4, IPv6 and opaque-host parsers are private members of ada::url,
+// and its host parser which dispatches to them is only defined in ada.cpp. An
+// explicit instantiation may name private members, which lets `get` return
+// their addresses.
+using host_parser = bool (ada::url::*)(std::string_view);
+
+template <typename Tag, host_parser Member>
+struct expose_host_parser {
+  friend host_parser get(Tag) { return Member; }
+};
+
+struct parse_ipv4_tag {
+  friend host_parser get(parse_ipv4_tag);
+};
+struct parse_ipv6_tag {
+  friend host_parser get(parse_ipv6_tag);
+};
+struct parse_opaque_host_tag {
+  friend host_parser get(parse_opaque_host_tag);
+};
+
+template struct expose_host_parser<parse_ipv4_tag, &ada::url::parse_ipv4>;
+template struct expose_host_parser<parse_ipv6_tag, &ada::url::parse_ipv6>;
+template struct expose_host_parser<parse_opaque_host_tag,
+                                   &ada::url::parse_opaque_host>;
+
+// The ends in a number checker of the URL Standard, which
+// ada::checkers::is_ipv4 implements but only defines in ada.cpp. The input is
+// lowercase.
+bool ends_in_a_number(std::string_view input) noexcept {
+  if (input.size() > 1 && input.back() == '.') {
+    input.remove_suffix(1);
+  }
+  size_t last_dot = input.rfind('.');
+  if (last_dot != std::string_view::npos) {
+    input.remove_prefix(last_dot + 1);
+  }
+  if (input.empty()) {
+    return false;
+  }
+  if (std::all_of(input.begin(), input.end(),
+                  [](char c) { return c >= '0' && c <= '9'; })) {
+    return true;
+  }
+  return ada::idna::begins_with(input, "0x") &&
+         std::all_of(input.begin() + 2, input.end(), [](char c) {
+           return (c >= '0' && c <= '9') || (c >= 'a' && c <= 'f');
+         });
+}
+
+// Follows ada::url::parse_host, writing the serialized host and its type to
+// `url`. The input must not be empty.
+bool parse_host(ada::url& url, std::string_view input, bool is_special) {
+  if (input.front() == '[') {
+    if (input.back() != ']') {
+      return false;
+    }
+    return (url.*get(parse_ipv6_tag{}))(input.substr(1, input.size() - 2));
+  }
+  if (!is_special) {
+    return (url.*get(parse_opaque_host_tag{}))(input);
+  }
+  std::optional<std::string> ascii;
+  if (!ada::unicode::to_ascii(ascii, input, input.find('%'))) {
+    return false;
+  }
+  if (ends_in_a_number(*ascii)) {
+    return (url.*get(parse_ipv4_tag{}))(*ascii);
+  }
+  url.host = std::move(ascii);
+  return true;
+}
+
+constexpr std::array<uint8_t, 32> add_to_percent_encode_set(
+    const uint8_t base[], std::initializer_list<char> characters) {
+  std::array<uint8_t, 32> set{};
+  for (size_t i = 0; i < set.size(); i++) {
+    set[i] = base[i];
+  }
+  for (char c : characters) {
+    set[uint8_t(c) >> 3] |= uint8_t(1 << (c & 7));
+  }
+  return set;
+}
+
+// The component percent-encode set is the userinfo percent-encode set and
+// U+0024 ($) to U+0026 (&), U+002B (+) and U+002C (,).
+constexpr std::array<uint8_t, 32> COMPONENT_PERCENT_ENCODE =
+    add_to_percent_encode_set(ada::character_sets::USERINFO_PERCENT_ENCODE,
+                              {'$', '%', '&', '+', ','});
+
+// The application/x-www-form-urlencoded percent-encode set is the component
+// percent-encode set and U+0021 (!), U+0027 (') to U+0029 RIGHT PARENTHESIS,
+// and U+007E (~). Ada's WWW_FORM_URLENCODED_PERCENT_ENCODE leaves spaces to
+// its serializer, which replaces them with '+'.
+constexpr std::array<uint8_t, 32> FORM_URLENCODED_PERCENT_ENCODE =
+    add_to_percent_encode_set(COMPONENT_PERCENT_ENCODE.data(),
+                              {'!', '\'', '(', ')', '~'});
+
+// Must match the discriminants of `ada_url::percent::EncodeSet`.
+const uint8_t* get_percent_encode_set(uint8_t set) noexcept {
+  switch (set) {
+    case 0:
+      return ada::character_sets::C0_CONTROL_PERCENT_ENCODE;
+    case 1:
+      return ada::character_sets::FRAGMENT_PERCENT_ENCODE;
+    case 2:
+      return ada::character_sets::QUERY_PERCENT_ENCODE;
+    case 3:
+      return ada::character_sets::SPECIAL_QUERY_PERCENT_ENCODE;
+    case 4:
+      return ada::character_sets::PATH_PERCENT_ENCODE;
+    case 5:
+      return ada::character_sets::USERINFO_PERCENT_ENCODE;
+    case 6:
+      return COMPONENT_PERCENT_ENCODE.data();
+    default:
+      return FORM_URLENCODED_PERCENT_ENCODE.data();
+  }
+}
+
+bool is_scalar_value(char32_t c) noexcept {
+  return c <= 0x10ffff && (c < 0xd800 || c > 0xdfff);
+}
+
+// Follows ada::idna::to_ascii, with the processing steps and validity
+// criteria selected by the options. Both the ASCII and Unicode forms of the
+// domain are computed. Returns false on error.
+bool process_domain(std::string_view input, const ada_idna_options& options,
+                    std::string& ascii, std::string& unicode) {
+  std::u32string utf32(
+      ada::idna::utf32_length_from_utf8(input.data(), input.size()), '\0');
+  if (ada::idna::utf8_to_utf32(input.data(), input.size(), utf32.data()) !=
+      utf32.size()) {
+    return false;
+  }
+  utf32 = ada::idna::map(utf32);
+  if (utf32.empty() && !input.empty()) {
+    return false;
+  }
+  if (options.transitional) {
+    utf32 = apply_transitional_mapping(utf32);
+  }
+  ada::idna::normalize(utf32);
+
+  size_t label_start = 0;
+  while (label_start != utf32.size()) {
+    size_t loc_dot = utf32.find('.', label_start);
+    bool is_last_label = (loc_dot == std::u32string_view::npos);
+    size_t label_size =
+        is_last_label ? utf32.size() - label_start : loc_dot - label_start;
+    std::u32string_view label_view(utf32.data() + label_start, label_size);
+    label_start += is_last_label ? label_size : label_size + 1;
+    size_t ascii_label_start = ascii.size();
+    if (label_size == 0) {
+      // Only the root label may be empty when verifying the DNS length.
+      if (options.verify_dns_length && !is_last_label) {
+        return false;
+      }
+    } else if (ada::idna::begins_with(label_view, U"xn--")) {
+      if (!is_ascii(label_view)) {
+        return false;
+      }
+      for (char32_t c : label_view) {
+        ascii += (unsigned char)(c);
+      }
+      std::string_view puny_segment_ascii(ascii.data() + ascii_label_start + 4,
+                                          label_size - 4);
+      std::u32string decoded;
+      if (!ada::idna::punycode_to_utf32(puny_segment_ascii, decoded)) {
+        return false;
+      }
+      std::u32string post_map = ada::idna::map(decoded);
+      if (decoded != post_map) {
+        return false;
+      }
+      ada::idna::normalize(post_map);
+      if (decoded != post_map || decoded.empty()) {
+        return false;
+      }
+      if (!is_label_valid(decoded, options)) {
+        return false;
+      }
+      append_utf8(unicode, decoded);
+    } else {
+      if (!is_label_valid(label_view, options)) {
+        return false;
+      }
+      if (is_ascii(label_view)) {
+        for (char32_t c : label_view) {
+          ascii += (unsigned char)(c);
+        }
+      } else {
+        ascii.append("xn--");
+        if (!ada::idna::utf32_to_punycode(label_view, ascii)) {
+          return false;
+        }
+      }
+      append_utf8(unicode, label_view);
+    }
+    if (options.verify_dns_length && ascii.size() - ascii_label_start > 63) {
+      return false;
+    }
+    if (!is_last_label) {
+      ascii.push_back('.');
+      unicode.push_back('.');
+    }
+  }
+
+  if (options.verify_dns_length) {
+    size_t length = ascii.size();
+    if (length > 0 && ascii.back() == '.') {
+      length--;
+    }
+    if (length == 0 || length > 253) {
+      return false;
+    }
+  }
+  return true;
+}
+
+}  // namespace
+
+extern "C" {
+
+typedef struct {
+  ada_string href;
+  ada_url_components components;
+  uint8_t host_type;
+  uint8_t scheme_type;
+} ada_url_snapshot;
+
+// Returns the href, components, host type and scheme type in a single call.
+// The href is owned by the ada_url instance.
+ada_url_snapshot ada_get_snapshot(ada_url result) noexcept {
+  static_assert(sizeof(ada_url_components) == sizeof(ada::url_components));
+  ada::result<ada::url_aggregator>& r = get_url(result);
+  ada_url_snapshot out{};
+  if (!r) {
+    return out;
+  }
+  out.href = make_string(r->get_href());
+  memcpy(&out.components, &r->get_components(), sizeof(ada_url_components));
+  out.host_type = r->host_type;
+  out.scheme_type = uint8_t(r->type);
+  return out;
+}
+
+// Returns true if the URL has an opaque path, e.g. `mailto:` URLs. The C API
+// of Ada does not expose it.
+bool ada_has_opaque_path(ada_url result) noexcept {
+  ada::result<ada::url_aggregator>& r = get_url(result);
+  return r && r->has_opaque_path;
+}
+
+// Returns an estimate of the bytes held by the URL: the result object itself
+// and the heap allocation of its serialization. Ada does not expose the
+// capacity of its buffer, so its length is used instead, and serializations
+// which fit in the small string optimization are not counted.
+size_t ada_memory_usage(ada_url result) noexcept {
+  ada::result<ada::url_aggregator>& r = get_url(result);
+  size_t usage = sizeof(ada::result<ada::url_aggregator>);
+  if (r) {
+    size_t length = r->get_href().size();
+    if (length > std::string().capacity()) {
+      usage += length + 1;
+    }
+  }
+  return usage;
+}
+
+// Parses `count` inputs, concatenated in `buffer` with their lengths in
+// `lengths`, against the already parsed `base`, writing the results to `out`.
+// The caller must free each result with ada_free.
+void ada_parse_many_with_base(ada_url base, const char* buffer,
+                              const size_t* lengths, size_t count,
+                              ada_url* out) noexcept {
+  ada::result<ada::url_aggregator>& b = get_url(base);
+  size_t offset = 0;
+  for (size_t i = 0; i < count; i++) {
+    std::string_view input(buffer + offset, lengths[i]);
+    offset += lengths[i];
+    out[i] = new ada::result<ada::url_aggregator>(
+        b ? ada::parse<ada::url_aggregator>(input, &b.value()) : b);
+  }
+}
+
+// Sets the first pair with the given name and removes the others, or appends
+// a pair if there is none, then writes the serialized pairs back as the
+// query, as `url.searchParams.set(name, value)` does.
+void ada_replace_query_param(ada_url result, const char* name,
+                             size_t name_length, const char* value,
+                             size_t value_length) noexcept {
+  ada::result<ada::url_aggregator>& r = get_url(result);
+  if (!r) {
+    return;
+  }
+  ada::url_search_params params(r->get_search());
+  params.set(std::string_view(name, name_length),
+             std::string_view(value, value_length));
+  r->set_search(params.to_string());
+}
+
+// Parses a host, without a port, with the host parser of the URL Standard, as
+// the host of a special URL if `is_special` is true and as an opaque host
+// otherwise. Returns false on error, in which case `out` and `host_type` are
+// not written. The caller must free the result with ada_free_owned_string.
+bool ada_parse_host(const char* input, size_t length, bool is_special,
+                    ada_owned_string* out, uint8_t* host_type) noexcept {
+  std::string_view view(input, length);
+  ada::url url;
+  if (view.empty() || !parse_host(url, view, is_special)) {
+    return false;
+  }
+  *out = make_owned_string(*url.host);
+  *host_type = uint8_t(url.host_type);
+  return true;
+}
+
+// Converts a domain to ASCII following UTS #46 with the given options.
+// Returns false on error, in which case `out` is not written. The caller must
+// free the result with ada_free_owned_string.
+bool ada_idna_to_ascii_with_options(const char* input, size_t length,
+                                    ada_idna_options options,
+                                    ada_owned_string* out) noexcept {
+  std::string ascii;
+  std::string unicode;
+  if (!process_domain(std::string_view(input, length), options, ascii,
+                      unicode)) {
+    return false;
+  }
+  *out = make_owned_string(ascii);
+  return true;
+}
+
+// Converts a domain to Unicode following UTS #46 with the given options.
+// Unlike ada_idna_to_unicode, the domain is validated and false is returned
+// on error, in which case `out` is not written. The caller must free the
+// result with ada_free_owned_string.
+bool ada_idna_to_unicode_with_options(const char* input, size_t length,
+                                      ada_idna_options options,
+                                      ada_owned_string* out) noexcept {
+  std::string ascii;
+  std::string unicode;
+  if (!process_domain(std::string_view(input, length), options, ascii,
+                      unicode)) {
+    return false;
+  }
+  *out = make_owned_string(unicode);
+  return true;
+}
+
+// Encodes a single label with Punycode, without the "xn--" prefix. Returns
+// false on error, in which case `out` is not written. The caller must free
+// the result with ada_free_owned_string.
+bool ada_idna_punycode_encode(const char* input, size_t length,
+                              ada_owned_string* out) noexcept {
+  std::u32string utf32(ada::idna::utf32_length_from_utf8(input, length), '\0');
+  if (ada::idna::utf8_to_utf32(input, length, utf32.data()) != utf32.size()) {
+    return false;
+  }
+  std::string encoded;
+  if (!ada::idna::utf32_to_punycode(utf32, encoded)) {
+    return false;
+  }
+  *out = make_owned_string(encoded);
+  return true;
+}
+
+// Decodes a single Punycode label, without the "xn--" prefix. Returns false
+// on error, in which case `out` is not written. The caller must free the
+// result with ada_free_owned_string.
+bool ada_idna_punycode_decode(const char* input, size_t length,
+                              ada_owned_string* out) noexcept {
+  std::u32string decoded;
+  if (!ada::idna::punycode_to_utf32(std::string_view(input, length),
+                                    decoded) ||
+      !std::all_of(decoded.begin(), decoded.end(), is_scalar_value)) {
+    return false;
+  }
+  std::string utf8;
+  append_utf8(utf8, decoded);
+  *out = make_owned_string(utf8);
+  return true;
+}
+
+// Maps a domain with the IDNA Mapping Table. Returns false if the domain
+// contains a disallowed code point, in which case `out` is not written. The
+// caller must free the result with ada_free_owned_string.
+bool ada_idna_map(const char* input, size_t length,
+                  ada_owned_string* out) noexcept {
+  std::u32string utf32(ada::idna::utf32_length_from_utf8(input, length), '\0');
+  if (ada::idna::utf8_to_utf32(input, length, utf32.data()) != utf32.size()) {
+    return false;
+  }
+  std::u32string mapped = ada::idna::map(utf32);
+  // Ada returns an empty string on error, which is also the result of mapping
+  // a domain made of ignored code points only. Prefixing a valid code point
+  // tells them apart.
+  if (mapped.empty() && !utf32.empty() &&
+      ada::idna::map(U"a" + utf32).empty()) {
+    return false;
+  }
+  std::string utf8;
+  append_utf8(utf8, mapped);
+  *out = make_owned_string(utf8);
+  return true;
+}
+
+// Normalizes a domain to Unicode Normalization Form C. The caller must free
+// the result with ada_free_owned_string.
+ada_owned_string ada_idna_normalize(const char* input, size_t length) noexcept {
+  std::u32string utf32(ada::idna::utf32_length_from_utf8(input, length), '\0');
+  utf32.resize(ada::idna::utf8_to_utf32(input, length, utf32.data()));
+  ada::idna::normalize(utf32);
+  std::string utf8;
+  append_utf8(utf8, utf32);
+  return make_owned_string(utf8);
+}
+
+// Checks the validity criteria of a single mapped and normalized label.
+bool ada_idna_is_label_valid(const char* input, size_t length) noexcept {
+  std::u32string utf32(ada::idna::utf32_length_from_utf8(input, length), '\0');
+  if (ada::idna::utf8_to_utf32(input, length, utf32.data()) != utf32.size()) {
+    return false;
+  }
+  return ada::idna::is_label_valid(utf32);
+}
+
+// Percent-encodes the input with the given encode set, see
+// get_percent_encode_set. The caller must free the result with
+// ada_free_owned_string.
+ada_owned_string ada_percent_encode(const char* input, size_t length,
+                                    uint8_t set) noexcept {
+  return make_owned_string(ada::unicode::percent_encode(
+      std::string_view(input, length), get_percent_encode_set(set)));
+}
+
+// Percent-decodes the input. The result may not be valid UTF-8. The caller
+// must free the result with ada_free_owned_string.
+ada_owned_string ada_percent_decode(const char* input, size_t length) noexcept {
+  std::string_view view(input, length);
+  return make_owned_string(ada::unicode::percent_decode(view, view.find('%')));
+}
+
+// Returns a deep copy of the search params, to be freed with
+// ada_free_search_params.
+ada_url_search_params ada_search_params_copy(
+    ada_url_search_params input) noexcept {
+  return new ada::result<ada::url_search_params>(
+      *(ada::result<ada::url_search_params>*)input);
+}
+
+// Returns the key/value pair at the given index, or empty strings if the
+// index is out of bounds. The strings are valid until the search params are
+// modified or freed.
+ada_string_pair ada_search_params_get_pair(ada_url_search_params result,
+                                           size_t index) noexcept {
+  auto& r = *(ada::result<ada::url_search_params>*)result;
+  if (!r || index >= r->size()) {
+    return ada_string_pair{make_string({}), make_string({})};
+  }
+  const auto& pair = *(r->begin() + index);
+  return ada_string_pair{make_string(pair.first), make_string(pair.second)};
+}
+
+// Appends `pair_count` pairs whose names and values are concatenated in
+// `buffer`, with their lengths alternating in `lengths`.
+void ada_search_params_append_many(ada_url_search_params result,
+                                   const char* buffer, const size_t* lengths,
+                                   size_t pair_count) noexcept {
+  auto& r = *(ada::result<ada::url_search_params>*)result;
+  if (!r) {
+    return;
+  }
+  size_t offset = 0;
+  for (size_t i = 0; i < pair_count; i++) {
+    std::string_view name(buffer + offset, lengths[2 * i]);
+    offset += lengths[2 * i];
+    std::string_view value(buffer + offset, lengths[2 * i + 1]);
+    offset += lengths[2 * i + 1];
+    r->append(name, value);
+  }
+}
+
+}  // extern "C"
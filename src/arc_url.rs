@@ -0,0 +1,155 @@
+use crate::{ParseUrlError, Url};
+use core::{borrow, fmt, ops};
+use std::sync::Arc;
+
+/// A cheaply clonable [`Url`] that shares the underlying ada object between clones.
+///
+/// Cloning an [`ArcUrl`] only increments an atomic reference count, whereas cloning a [`Url`]
+/// performs a deep copy over FFI. Mutations go through [`ArcUrl::make_mut`], which copies the
+/// URL only if it is shared with other clones (copy-on-write).
+///
+/// This type is only available if the `std` Cargo feature is enabled.
+///
+/// ```
+/// use ada_url::ArcUrl;
+///
+/// let first = ArcUrl::parse("https://example.com/foo", None).expect("Invalid URL");
+/// let mut second = first.clone();
+/// assert!(ArcUrl::ptr_eq(&first, &second));
+///
+/// second.make_mut().set_pathname(Some("/bar")).unwrap();
+/// assert_eq!(first.href(), "https://example.com/foo");
+/// assert_eq!(second.href(), "https://example.com/bar");
+/// ```
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ArcUrl(Arc<Url>);
+
+impl ArcUrl {
+    /// Parses the input with an optional base. See [`Url::parse`].
+    pub fn parse<Input>(input: Input, base: Option<&str>) -> Result<Self, ParseUrlError<Input>>
+    where
+        Input: AsRef<str>,
+    {
+        Url::parse(input, base).map(Self::from)
+    }
+
+    /// Returns a mutable reference to the URL, copying it first if it is shared with other
+    /// clones.
+    pub fn make_mut(&mut self) -> &mut Url {
+        Arc::make_mut(&mut self.0)
+    }
+
+    /// Returns the inner [`Url`], copying it only if it is shared with other clones.
+    ///
+    /// ```
+    /// use ada_url::ArcUrl;
+    ///
+    /// let url = ArcUrl::parse("https://example.com", None).expect("Invalid URL");
+    /// assert_eq!(url.into_url().href(), "https://example.com/");
+    /// ```
+    #[must_use]
+    pub fn into_url(self) -> Url {
+        Arc::try_unwrap(self.0).unwrap_or_else(|shared| (*shared).clone())
+    }
+
+    /// Returns true if both values share the same underlying URL.
+    #[must_use]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.0, &other.0)
+    }
+
+    /// Returns the number of clones sharing the underlying URL.
+    #[must_use]
+    pub fn strong_count(this: &Self) -> usize {
+        Arc::strong_count(&this.0)
+    }
+}
+
+impl From<Url> for ArcUrl {
+    fn from(value: Url) -> Self {
+        Self(Arc::new(value))
+    }
+}
+
+impl From<ArcUrl> for Url {
+    fn from(value: ArcUrl) -> Self {
+        value.into_url()
+    }
+}
+
+impl ops::Deref for ArcUrl {
+    type Target = Url;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<Url> for ArcUrl {
+    fn as_ref(&self) -> &Url {
+        &self.0
+    }
+}
+
+impl AsRef<str> for ArcUrl {
+    fn as_ref(&self) -> &str {
+        self.0.href()
+    }
+}
+
+impl borrow::Borrow<str> for ArcUrl {
+    fn borrow(&self) -> &str {
+        self.0.href()
+    }
+}
+
+impl fmt::Debug for ArcUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for ArcUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl core::str::FromStr for ArcUrl {
+    type Err = ParseUrlError<Box<str>>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<Url>().map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ArcUrl, Url};
+
+    #[test]
+    fn should_share_until_mutated() {
+        let first = ArcUrl::parse("https://example.com", None).unwrap();
+        let mut second = first.clone();
+        assert_eq!(ArcUrl::strong_count(&first), 2);
+
+        second.make_mut().set_hash(Some("top"));
+        assert!(!ArcUrl::ptr_eq(&first, &second));
+        assert_eq!(ArcUrl::strong_count(&first), 1);
+        assert_eq!(first.hash(), "");
+        assert_eq!(second.hash(), "#top");
+
+        // Unshared values are mutated in place.
+        let before: *const Url = &*second;
+        second.make_mut().set_hash(None);
+        assert_eq!(before, &*second as *const Url);
+    }
+
+    #[test]
+    fn should_convert_from_and_into_url() {
+        let url = Url::parse("https://example.com", None).unwrap();
+        let shared = ArcUrl::from(url.clone());
+        assert_eq!(*shared, url);
+        assert_eq!(Url::from(shared.clone()), url);
+        assert_eq!(shared.to_string(), "https://example.com/");
+    }
+}
@@ -0,0 +1,229 @@
+//! Cheap predicates used by the URL Standard and Ada to classify parts of a URL, e.g. to
+//! sanitize hosts or paths before parsing them.
+//!
+//! The checkers of Ada are inlined into its parser and not part of its C API, so they are
+//! implemented here with the same behavior, and do not allocate.
+//!
+//! ```
+//! use ada_url::checkers;
+//!
+//! assert!(checkers::is_ipv4("0x7f.1"));
+//! assert!(checkers::ends_in_a_number("example.123"));
+//! assert!(!checkers::verify_dns_length("a..b"));
+//! assert!(checkers::has_tabs_or_newline("https://exa\tmple.com"));
+//! ```
+
+/// Returns whether `input` has an ASCII tab or newline, which the URL parser removes from
+/// its input.
+#[must_use]
+pub fn has_tabs_or_newline(input: &str) -> bool {
+    input.bytes().any(|c| matches!(c, b'\t' | b'\n' | b'\r'))
+}
+
+/// Returns whether `c` is a [forbidden host code point](https://url.spec.whatwg.org/#forbidden-host-code-point),
+/// which is not allowed in any host.
+#[must_use]
+pub fn is_forbidden_host_code_point(c: char) -> bool {
+    c.is_ascii() && b"\0\t\n\r #/:<>?@[\\]^|".contains(&(c as u8))
+}
+
+/// Returns whether `c` is a [forbidden domain code point](https://url.spec.whatwg.org/#forbidden-domain-code-point),
+/// which is not allowed in the domain of a special URL, after percent-decoding.
+#[must_use]
+pub fn is_forbidden_domain_code_point(c: char) -> bool {
+    is_forbidden_host_code_point(c) || c.is_ascii_control() || c == '%'
+}
+
+/// Returns whether the last label of `input`, ignoring a trailing dot, is a number, in which
+/// case the host parser of special URLs parses `input` as an IPv4 address, and fails if it
+/// is not a valid one.
+///
+/// For more information, read the [URL Standard](https://url.spec.whatwg.org/#ends-in-a-number-checker).
+///
+/// ```
+/// use ada_url::checkers;
+///
+/// assert!(checkers::ends_in_a_number("1.2.3.4."));
+/// assert!(checkers::ends_in_a_number("example.0x"));
+/// assert!(checkers::ends_in_a_number("999.1.1.1"));
+/// assert!(!checkers::ends_in_a_number("example.com"));
+/// assert!(!checkers::ends_in_a_number("1.2.3.0xg"));
+/// ```
+#[must_use]
+pub fn ends_in_a_number(input: &str) -> bool {
+    let input = match input.strip_suffix('.') {
+        Some(rest) if !rest.is_empty() => rest,
+        _ => input,
+    };
+    let last = input.rsplit('.').next().unwrap_or(input);
+    (!last.is_empty() && last.bytes().all(|c| c.is_ascii_digit())) || parse_number(last).is_some()
+}
+
+/// Returns whether `input` is a valid IPv4 address for the host parser of special URLs, which
+/// allows one to four parts in decimal, octal with a leading `0`, or hexadecimal with a
+/// leading `0x`, and an optional trailing dot.
+///
+/// ```
+/// use ada_url::checkers;
+///
+/// assert!(checkers::is_ipv4("127.0.0.1"));
+/// assert!(checkers::is_ipv4("0177.0x0.1."));
+/// assert!(checkers::is_ipv4("4294967295"));
+/// assert!(!checkers::is_ipv4("4294967296"));
+/// assert!(!checkers::is_ipv4("1.256.1"));
+/// assert!(!checkers::is_ipv4("1.2.3.4.5"));
+/// ```
+#[must_use]
+pub fn is_ipv4(input: &str) -> bool {
+    let input = match input.strip_suffix('.') {
+        Some(rest) if !rest.is_empty() => rest,
+        _ => input,
+    };
+    let parts = input.split('.').count();
+    if parts > 4 {
+        return false;
+    }
+    input.split('.').enumerate().all(|(i, part)| {
+        let limit = if i + 1 == parts {
+            1u64 << (8 * (5 - parts))
+        } else {
+            256
+        };
+        !part.is_empty() && parse_number(part).is_some_and(|number| number < limit)
+    })
+}
+
+/// Parses an [IPv4 number](https://url.spec.whatwg.org/#ipv4-number-parser), saturating at
+/// `u64::MAX` instead of overflowing.
+fn parse_number(input: &str) -> Option<u64> {
+    let (digits, radix) = if let Some(hex) = input
+        .strip_prefix("0x")
+        .or_else(|| input.strip_prefix("0X"))
+    {
+        (hex, 16)
+    } else if input.len() >= 2 && input.starts_with('0') {
+        (&input[1..], 8)
+    } else if input.is_empty() {
+        return None;
+    } else {
+        (input, 10)
+    };
+    digits.chars().try_fold(0u64, |number, c| {
+        Some(
+            number
+                .saturating_mul(radix.into())
+                .saturating_add(c.to_digit(radix)?.into()),
+        )
+    })
+}
+
+/// Returns whether the lengths of `domain` and its labels are valid for DNS, i.e. the domain
+/// has at most 253 bytes, or 254 with a trailing dot, and each label has between 1 and 63
+/// bytes.
+///
+/// ```
+/// use ada_url::checkers;
+///
+/// assert!(checkers::verify_dns_length("example.com."));
+/// assert!(!checkers::verify_dns_length(""));
+/// assert!(!checkers::verify_dns_length(&"a".repeat(64)));
+/// ```
+#[must_use]
+pub fn verify_dns_length(domain: &str) -> bool {
+    let labels = domain.strip_suffix('.').unwrap_or(domain);
+    labels.len() <= 253
+        && labels
+            .split('.')
+            .all(|label| (1..=63).contains(&label.len()))
+}
+
+/// Returns whether `input` starts with a [Windows drive letter](https://url.spec.whatwg.org/#start-with-a-windows-drive-letter),
+/// e.g. `C:` or `c|`, followed by the end of the input, `/`, `\`, `?` or `#`.
+///
+/// ```
+/// use ada_url::checkers;
+///
+/// assert!(checkers::starts_with_windows_drive_letter("C:/Windows"));
+/// assert!(checkers::starts_with_windows_drive_letter("c|"));
+/// assert!(!checkers::starts_with_windows_drive_letter("C:Windows"));
+/// ```
+#[must_use]
+pub fn starts_with_windows_drive_letter(input: &str) -> bool {
+    match input.as_bytes() {
+        [letter, b':' | b'|', rest @ ..] => {
+            letter.is_ascii_alphabetic()
+                && rest
+                    .first()
+                    .map_or(true, |c| matches!(c, b'/' | b'\\' | b'?' | b'#'))
+        }
+        _ => false,
+    }
+}
+
+/// Returns whether `input` is a [normalized Windows drive letter](https://url.spec.whatwg.org/#normalized-windows-drive-letter),
+/// i.e. an ASCII letter followed by `:`.
+#[must_use]
+pub fn is_normalized_windows_drive_letter(input: &str) -> bool {
+    matches!(input.as_bytes(), [letter, b':'] if letter.is_ascii_alphabetic())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{checkers, HostType, Url};
+
+    #[test]
+    fn should_classify_ipv4_hosts_like_the_parser() {
+        let hosts = [
+            "1.2.3.4",
+            "1.2.3.4.",
+            "1.2.3.4..",
+            "0x",
+            "0x.0x.0x.0x",
+            "08",
+            "0xffffffff",
+            "0x100000000",
+            "1.16777215",
+            "1.16777216",
+            "00000000000000000000000000001",
+            "99999999999999999999",
+            "1..2",
+            ".1",
+            "example.com",
+            "example.1",
+            "1.example",
+        ];
+        for host in hosts {
+            let mut url = Url::parse("http://h/", None).unwrap();
+            assert_eq!(
+                url.set_hostname(Some(host)).is_ok() && url.host_type() == HostType::IPV4,
+                checkers::is_ipv4(host),
+                "{host}"
+            );
+            if !checkers::is_ipv4(host) {
+                assert_eq!(
+                    url.set_hostname(Some(host)).is_err(),
+                    checkers::ends_in_a_number(host),
+                    "{host}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn should_verify_dns_length() {
+        let mut domain = [b'a'; 254];
+        for i in [63, 127, 191] {
+            domain[i] = b'.';
+        }
+        fn domain_str(domain: &[u8]) -> &str {
+            core::str::from_utf8(domain).unwrap()
+        }
+
+        assert!(checkers::verify_dns_length(domain_str(&domain[..253])));
+        assert!(!checkers::verify_dns_length(domain_str(&domain)));
+        domain[253] = b'.';
+        assert!(checkers::verify_dns_length(domain_str(&domain)));
+        assert!(!checkers::verify_dns_length("."));
+        assert!(!checkers::verify_dns_length(".a"));
+    }
+}
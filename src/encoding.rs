@@ -0,0 +1,191 @@
+//! Legacy encodings of the query string, through `encoding_rs`.
+
+use crate::percent::{self, EncodeSet};
+use crate::{ParseUrlError, SchemeType, Url};
+use alloc::string::String;
+use core::fmt::Write;
+use encoding_rs::{EncoderResult, Encoding, UTF_8};
+
+impl Url {
+    /// Parses the input with an optional base, encoding the query with `encoding` rather than
+    /// UTF-8 like the `encoding` argument of the WHATWG URL parser, which browsers set to the
+    /// encoding of the document.
+    ///
+    /// As in browsers, the encoding only applies to the query of special URLs other than `ws:`
+    /// and `wss:`, and the characters `encoding` cannot represent are written as percent-encoded
+    /// HTML numeric character references.
+    ///
+    /// This method is only available if the `encoding` Cargo feature is enabled.
+    ///
+    /// ```
+    /// use ada_url::Url;
+    /// use encoding_rs::WINDOWS_1251;
+    ///
+    /// let url = Url::parse_with_encoding("https://example.com/д?q=д€😀", None, WINDOWS_1251)
+    ///     .expect("This is a valid URL. Should have parsed it.");
+    /// assert_eq!(url.href(), "https://example.com/%D0%B4?q=%E4%88%26%23128512%3B");
+    /// ```
+    pub fn parse_with_encoding<Input>(
+        input: Input,
+        base: Option<&str>,
+        encoding: &'static Encoding,
+    ) -> Result<Self, ParseUrlError<Input>>
+    where
+        Input: AsRef<str>,
+    {
+        let Ok(url) = Self::parse(input.as_ref(), base) else {
+            return Err(ParseUrlError { input });
+        };
+        let encoding = encoding.output_encoding();
+        if encoding == UTF_8
+            || matches!(
+                url.scheme_type(),
+                SchemeType::NotSpecial | SchemeType::Ws | SchemeType::Wss
+            )
+        {
+            return Ok(url);
+        }
+
+        // The query of a special URL starts at the first `?` in the input, unless the fragment
+        // starts before it, and the parser strips tabs, newlines and the leading and trailing C0
+        // controls and spaces first.
+        let trimmed: String = input
+            .as_ref()
+            .trim_matches(|c: char| c <= ' ')
+            .chars()
+            .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+            .collect();
+        let Some(query_start) = trimmed.find(['?', '#']).filter(|&i| &trimmed[i..=i] == "?") else {
+            return Ok(url);
+        };
+        let query_end = trimmed[query_start..]
+            .find('#')
+            .map_or(trimmed.len(), |i| query_start + i);
+        let mut encoded = String::from(&trimmed[..=query_start]);
+        percent_encode_after_encoding(
+            &trimmed[query_start + 1..query_end],
+            encoding,
+            EncodeSet::SpecialQuery,
+            &mut encoded,
+        );
+        encoded.push_str(&trimmed[query_end..]);
+        Self::parse(encoded.as_str(), base).map_err(|_| ParseUrlError { input })
+    }
+}
+
+/// Encodes `input` with `encoding` and percent-encodes the resulting bytes which belong to
+/// `set`, writing the characters `encoding` cannot represent as percent-encoded HTML numeric
+/// character references, as in
+/// [percent-encode after encoding](https://url.spec.whatwg.org/#string-percent-encode-after-encoding).
+pub(crate) fn percent_encode_after_encoding(
+    input: &str,
+    encoding: &'static Encoding,
+    set: EncodeSet,
+    output: &mut String,
+) {
+    let mut encoder = encoding.output_encoding().new_encoder();
+    let mut buffer = [0; 1024];
+    let mut input = input;
+    loop {
+        let (result, read, written) =
+            encoder.encode_from_utf8_without_replacement(input, &mut buffer, true);
+        input = &input[read..];
+        append_percent_encoded(&buffer[..written], set, output);
+        match result {
+            EncoderResult::InputEmpty => break,
+            EncoderResult::OutputFull => {}
+            EncoderResult::Unmappable(c) => {
+                let _ = write!(output, "%26%23{}%3B", u32::from(c));
+            }
+        }
+    }
+}
+
+fn append_percent_encoded(bytes: &[u8], set: EncodeSet, output: &mut String) {
+    let mut rest = bytes;
+    while !rest.is_empty() {
+        let ascii_len = rest
+            .iter()
+            .position(|byte| !byte.is_ascii())
+            .unwrap_or(rest.len());
+        if let Ok(ascii) = core::str::from_utf8(&rest[..ascii_len]) {
+            output.push_str(&percent::encode(ascii, set));
+        }
+        if let Some(byte) = rest.get(ascii_len) {
+            let _ = write!(output, "%{byte:02X}");
+        }
+        rest = rest.get(ascii_len + 1..).unwrap_or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Url;
+    use encoding_rs::{ISO_2022_JP, SHIFT_JIS, UTF_16LE, WINDOWS_1251, WINDOWS_1252};
+
+    #[test]
+    fn should_encode_query_with_encoding() {
+        let cases = [
+            (
+                "https://example.com/?q=é",
+                WINDOWS_1252,
+                "https://example.com/?q=%E9",
+            ),
+            (
+                "https://example.com/?q=é",
+                UTF_16LE,
+                "https://example.com/?q=%C3%A9",
+            ),
+            (
+                "https://example.com/?q=日本",
+                SHIFT_JIS,
+                "https://example.com/?q=%93%FA%96{",
+            ),
+            (
+                "https://example.com/?q=日&r",
+                ISO_2022_JP,
+                "https://example.com/?q=%1B$BF|%1B(B&r",
+            ),
+            // Only the query is affected.
+            (
+                " https://ж.example/ж?ж\t#ж ",
+                WINDOWS_1251,
+                "https://xn--f1a.example/%D0%B6?%E6#%D0%B6",
+            ),
+            (
+                "https://example.com/#?ж",
+                WINDOWS_1251,
+                "https://example.com/#?%D0%B6",
+            ),
+            (
+                "https://example.com/?%D0%B6 '",
+                WINDOWS_1251,
+                "https://example.com/?%D0%B6%20%27",
+            ),
+            (
+                "foo://example.com/?ж",
+                WINDOWS_1251,
+                "foo://example.com/?%D0%B6",
+            ),
+            (
+                "wss://example.com/?ж",
+                WINDOWS_1251,
+                "wss://example.com/?%D0%B6",
+            ),
+        ];
+        for (input, encoding, expected) in cases {
+            let url = Url::parse_with_encoding(input, None, encoding).unwrap();
+            assert_eq!(url.href(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn should_encode_relative_query_with_encoding() {
+        let base = Some("https://example.com/a?b");
+        let url = Url::parse_with_encoding("?ж", base, WINDOWS_1251).unwrap();
+        assert_eq!(url.href(), "https://example.com/a?%E6");
+        let url = Url::parse_with_encoding("c", base, WINDOWS_1251).unwrap();
+        assert_eq!(url.href(), "https://example.com/c");
+        assert!(Url::parse_with_encoding("?ж", None, WINDOWS_1251).is_err());
+    }
+}
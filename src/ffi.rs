@@ -62,6 +62,16 @@ impl AsRef<str> for ada_owned_string {
     }
 }
 
+impl ada_owned_string {
+    /// Returns the bytes of strings which may not be valid UTF-8, e.g. percent-decoded input.
+    pub fn as_bytes(&self) -> &[u8] {
+        if self.length == 0 {
+            return &[];
+        }
+        unsafe { core::slice::from_raw_parts(self.data.cast(), self.length) }
+    }
+}
+
 #[cfg(feature = "std")]
 impl Display for ada_owned_string {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -126,6 +136,25 @@ pub struct ada_url_components {
     pub hash_start: u32,
 }
 
+/// Returned by [`ada_get_snapshot`], defined in `deps/ada_ext.cpp`.
+#[repr(C)]
+pub struct ada_url_snapshot {
+    pub href: ada_string,
+    pub components: ada_url_components,
+    pub host_type: u8,
+    pub scheme_type: u8,
+}
+
+/// Passed to [`ada_idna_to_ascii_with_options`], defined in `deps/ada_ext.cpp`.
+#[repr(C)]
+pub struct ada_idna_options {
+    pub be_strict: bool,
+    pub check_hyphens: bool,
+    pub check_bidi: bool,
+    pub verify_dns_length: bool,
+    pub transitional: bool,
+}
+
 extern "C" {
     pub fn ada_parse(input: *const c_char, length: usize) -> *mut ada_url;
     pub fn ada_parse_with_base(
@@ -134,6 +163,20 @@ extern "C" {
         base: *const c_char,
         base_length: usize,
     ) -> *mut ada_url;
+    pub fn ada_parse_many_with_base(
+        base: *mut ada_url,
+        buffer: *const c_char,
+        lengths: *const usize,
+        count: usize,
+        out: *mut *mut ada_url,
+    );
+    pub fn ada_parse_host(
+        input: *const c_char,
+        length: usize,
+        is_special: bool,
+        out: *mut ada_owned_string,
+        host_type: *mut u8,
+    ) -> bool;
     pub fn ada_free(url: *mut ada_url);
     pub fn ada_free_owned_string(url: ada_owned_string);
     pub fn ada_copy(url: *mut ada_url) -> *mut ada_url;
@@ -146,6 +189,7 @@ extern "C" {
         base_length: usize,
     ) -> bool;
     pub fn ada_get_components(url: *mut ada_url) -> *mut ada_url_components;
+    pub fn ada_get_snapshot(url: *mut ada_url) -> ada_url_snapshot;
 
     // Getters
     pub fn ada_get_origin(url: *mut ada_url) -> ada_owned_string;
@@ -173,6 +217,13 @@ extern "C" {
     pub fn ada_set_pathname(url: *mut ada_url, input: *const c_char, length: usize) -> bool;
     pub fn ada_set_search(url: *mut ada_url, input: *const c_char, length: usize);
     pub fn ada_set_protocol(url: *mut ada_url, input: *const c_char, length: usize) -> bool;
+    pub fn ada_replace_query_param(
+        url: *mut ada_url,
+        name: *const c_char,
+        name_length: usize,
+        value: *const c_char,
+        value_length: usize,
+    );
 
     // Clear methods
     pub fn ada_clear_search(url: *mut ada_url);
@@ -189,10 +240,41 @@ extern "C" {
     pub fn ada_has_password(url: *mut ada_url) -> bool;
     pub fn ada_has_hash(url: *mut ada_url) -> bool;
     pub fn ada_has_search(url: *mut ada_url) -> bool;
+    pub fn ada_has_opaque_path(url: *mut ada_url) -> bool;
+    pub fn ada_memory_usage(url: *mut ada_url) -> usize;
 
     // IDNA methods
     pub fn ada_idna_to_unicode(input: *const c_char, length: usize) -> ada_owned_string;
     pub fn ada_idna_to_ascii(input: *const c_char, length: usize) -> ada_owned_string;
+    pub fn ada_idna_to_ascii_with_options(
+        input: *const c_char,
+        length: usize,
+        options: ada_idna_options,
+        out: *mut ada_owned_string,
+    ) -> bool;
+    pub fn ada_idna_to_unicode_with_options(
+        input: *const c_char,
+        length: usize,
+        options: ada_idna_options,
+        out: *mut ada_owned_string,
+    ) -> bool;
+    pub fn ada_idna_punycode_encode(
+        input: *const c_char,
+        length: usize,
+        out: *mut ada_owned_string,
+    ) -> bool;
+    pub fn ada_idna_punycode_decode(
+        input: *const c_char,
+        length: usize,
+        out: *mut ada_owned_string,
+    ) -> bool;
+    pub fn ada_idna_map(input: *const c_char, length: usize, out: *mut ada_owned_string) -> bool;
+    pub fn ada_idna_normalize(input: *const c_char, length: usize) -> ada_owned_string;
+    pub fn ada_idna_is_label_valid(input: *const c_char, length: usize) -> bool;
+
+    // Percent-encoding
+    pub fn ada_percent_encode(input: *const c_char, length: usize, set: u8) -> ada_owned_string;
+    pub fn ada_percent_decode(input: *const c_char, length: usize) -> ada_owned_string;
 
     // URLSearchParams
     pub fn ada_parse_search_params(
@@ -200,8 +282,20 @@ extern "C" {
         length: usize,
     ) -> *mut ada_url_search_params;
     pub fn ada_free_search_params(search_params: *mut ada_url_search_params);
+    pub fn ada_search_params_copy(
+        search_params: *mut ada_url_search_params,
+    ) -> *mut ada_url_search_params;
+    pub fn ada_search_params_get_pair(
+        search_params: *mut ada_url_search_params,
+        index: usize,
+    ) -> ada_string_pair;
     pub fn ada_search_params_size(search_params: *mut ada_url_search_params) -> usize;
     pub fn ada_search_params_sort(search_params: *mut ada_url_search_params);
+    pub fn ada_search_params_reset(
+        search_params: *mut ada_url_search_params,
+        input: *const c_char,
+        length: usize,
+    );
     pub fn ada_search_params_to_string(
         search_params: *mut ada_url_search_params,
     ) -> ada_owned_string;
@@ -212,6 +306,12 @@ extern "C" {
         value: *const c_char,
         value_length: usize,
     );
+    pub fn ada_search_params_append_many(
+        search_params: *mut ada_url_search_params,
+        buffer: *const c_char,
+        lengths: *const usize,
+        pair_count: usize,
+    );
     pub fn ada_search_params_set(
         search_params: *mut ada_url_search_params,
         name: *const c_char,
@@ -0,0 +1,361 @@
+//! Serialization of name-value pairs to and parsing from
+//! [`application/x-www-form-urlencoded`](https://url.spec.whatwg.org/#concept-urlencoded-serializer)
+//! strings, with control over how spaces and other characters are escaped.
+//!
+//! This module is only available if the `alloc` Cargo feature is enabled.
+//!
+//! ```
+//! use ada_url::form_urlencoded::{self, Serializer, Spaces};
+//!
+//! let pairs = [("q", "a b+c"), ("lang", "fr")];
+//! assert_eq!(form_urlencoded::serialize(pairs), "q=a+b%2Bc&lang=fr");
+//!
+//! let serializer = Serializer {
+//!     spaces: Spaces::Percent,
+//!     ..Serializer::default()
+//! };
+//! assert_eq!(serializer.serialize(pairs), "q=a%20b%2Bc&lang=fr");
+//! ```
+
+use crate::percent::{self, EncodeSet};
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+/// How [`Serializer`] escapes spaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Spaces {
+    /// Spaces are replaced with `+`, and `+` is percent-encoded, as HTML forms do.
+    #[default]
+    Plus,
+    /// Spaces are percent-encoded as `%20`, and `+` is escaped according to the encode set.
+    Percent,
+}
+
+/// Options of the `application/x-www-form-urlencoded` serializer.
+///
+/// The default matches the serializer of the WHATWG URL Standard, which `UrlSearchParams` uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Serializer {
+    /// How spaces are escaped.
+    pub spaces: Spaces,
+    /// The characters which are percent-encoded, in addition to spaces and non-ASCII characters.
+    pub encode_set: EncodeSet,
+}
+
+impl Default for Serializer {
+    fn default() -> Self {
+        Self {
+            spaces: Spaces::Plus,
+            encode_set: EncodeSet::FormUrlencoded,
+        }
+    }
+}
+
+impl Serializer {
+    /// Serializes `pairs` as `name=value` joined with `&`.
+    ///
+    /// ```
+    /// use ada_url::form_urlencoded::{Serializer, Spaces};
+    /// use ada_url::percent::EncodeSet;
+    ///
+    /// let serializer = Serializer {
+    ///     spaces: Spaces::Percent,
+    ///     encode_set: EncodeSet::Query,
+    /// };
+    /// assert_eq!(serializer.serialize([("path", "/a b/é")]), "path=/a%20b/%C3%A9");
+    /// ```
+    #[must_use]
+    pub fn serialize<Input, I>(&self, pairs: I) -> String
+    where
+        Input: AsRef<str>,
+        I: IntoIterator<Item = (Input, Input)>,
+    {
+        self.serialize_with(pairs, |input, output| {
+            output.push_str(&percent::encode(input, self.encode_set));
+        })
+    }
+
+    /// Serializes `pairs` like [`Serializer::serialize`], encoding names and values with
+    /// `encoding` rather than UTF-8. The characters `encoding` cannot represent are written as
+    /// percent-encoded HTML numeric character references, as browsers submit forms.
+    ///
+    /// This method is only available if the `encoding` Cargo feature is enabled.
+    ///
+    /// ```
+    /// use ada_url::form_urlencoded::Serializer;
+    /// use encoding_rs::WINDOWS_1251;
+    ///
+    /// let serializer = Serializer::default();
+    /// assert_eq!(
+    ///     serializer.serialize_with_encoding([("q", "да нет"), ("e", "€😀")], WINDOWS_1251),
+    ///     "q=%E4%E0+%ED%E5%F2&e=%88%26%23128512%3B"
+    /// );
+    /// ```
+    #[cfg(feature = "encoding")]
+    #[must_use]
+    pub fn serialize_with_encoding<Input, I>(
+        &self,
+        pairs: I,
+        encoding: &'static encoding_rs::Encoding,
+    ) -> String
+    where
+        Input: AsRef<str>,
+        I: IntoIterator<Item = (Input, Input)>,
+    {
+        self.serialize_with(pairs, |input, output| {
+            crate::encoding::percent_encode_after_encoding(
+                input,
+                encoding,
+                self.encode_set,
+                output,
+            );
+        })
+    }
+
+    fn serialize_with<Input, I>(&self, pairs: I, encode: impl Fn(&str, &mut String)) -> String
+    where
+        Input: AsRef<str>,
+        I: IntoIterator<Item = (Input, Input)>,
+    {
+        let mut output = String::new();
+        for (name, value) in pairs {
+            if !output.is_empty() {
+                output.push('&');
+            }
+            self.append_encoded(&mut output, name.as_ref(), &encode);
+            output.push('=');
+            self.append_encoded(&mut output, value.as_ref(), &encode);
+        }
+        output
+    }
+
+    fn append_encoded(
+        &self,
+        output: &mut String,
+        input: &str,
+        encode: &impl Fn(&str, &mut String),
+    ) {
+        let separators: &[char] = match self.spaces {
+            Spaces::Plus => &[' ', '+'],
+            Spaces::Percent => &[' '],
+        };
+        let mut rest = input;
+        while let Some(index) = rest.find(separators) {
+            encode(&rest[..index], output);
+            output.push_str(match (rest.as_bytes()[index], self.spaces) {
+                (b'+', _) => "%2B",
+                (_, Spaces::Plus) => "+",
+                (_, Spaces::Percent) => "%20",
+            });
+            rest = &rest[index + 1..];
+        }
+        encode(rest, output);
+    }
+}
+
+/// Serializes `pairs` with the default [`Serializer`].
+///
+/// ```
+/// use ada_url::form_urlencoded;
+/// assert_eq!(form_urlencoded::serialize([("a", "1 2"), ("b", "é&")]), "a=1+2&b=%C3%A9%26");
+/// ```
+#[must_use]
+pub fn serialize<Input, I>(pairs: I) -> String
+where
+    Input: AsRef<str>,
+    I: IntoIterator<Item = (Input, Input)>,
+{
+    Serializer::default().serialize(pairs)
+}
+
+/// Parses an `application/x-www-form-urlencoded` string into its decoded name-value pairs,
+/// borrowing from `input` the names and values which need no decoding. Invalid UTF-8 sequences
+/// are replaced with U+FFFD REPLACEMENT CHARACTER.
+///
+/// ```
+/// use ada_url::form_urlencoded;
+///
+/// let pairs: Vec<_> = form_urlencoded::parse("q=a+b%2Bc&&flag&%FF=1").collect();
+/// assert_eq!(
+///     pairs,
+///     [
+///         ("q".into(), "a b+c".into()),
+///         ("flag".into(), "".into()),
+///         ("\u{fffd}".into(), "1".into())
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn parse(input: &str) -> Parse<'_> {
+    Parse { input }
+}
+
+/// Iterator over the decoded name-value pairs of an `application/x-www-form-urlencoded` string,
+/// returned by [`parse`].
+#[derive(Debug, Clone)]
+pub struct Parse<'a> {
+    input: &'a str,
+}
+
+impl<'a> Iterator for Parse<'a> {
+    type Item = (Cow<'a, str>, Cow<'a, str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.input.is_empty() {
+                return None;
+            }
+            let (pair, rest) = self.input.split_once('&').unwrap_or((self.input, ""));
+            self.input = rest;
+            if !pair.is_empty() {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                return Some((decode(name), decode(value)));
+            }
+        }
+    }
+}
+
+impl core::iter::FusedIterator for Parse<'_> {}
+
+fn decode(input: &str) -> Cow<'_, str> {
+    if !input.contains(['+', '%']) {
+        return Cow::Borrowed(input);
+    }
+    let bytes = percent::decode(&input.replace('+', " "));
+    match String::from_utf8(bytes) {
+        Ok(decoded) => Cow::Owned(decoded),
+        Err(error) => Cow::Owned(String::from_utf8_lossy(error.as_bytes()).into_owned()),
+    }
+}
+
+/// Parses an `application/x-www-form-urlencoded` string whose percent-encoded bytes are in
+/// `encoding` rather than UTF-8, as submitted by forms of pages in a legacy encoding. Bytes
+/// `encoding` cannot decode are replaced with U+FFFD REPLACEMENT CHARACTER.
+///
+/// This function is only available if the `encoding` Cargo feature is enabled.
+///
+/// ```
+/// use ada_url::form_urlencoded;
+/// use encoding_rs::WINDOWS_1251;
+///
+/// assert_eq!(
+///     form_urlencoded::parse_with_encoding("q=%E4%E0+%ED%E5%F2&&flag", WINDOWS_1251),
+///     [("q".to_owned(), "да нет".to_owned()), ("flag".to_owned(), String::new())]
+/// );
+/// ```
+#[cfg(feature = "encoding")]
+#[must_use]
+pub fn parse_with_encoding(
+    input: &str,
+    encoding: &'static encoding_rs::Encoding,
+) -> alloc::vec::Vec<(String, String)> {
+    let decode = |input: &str| {
+        let bytes = percent::decode(&input.replace('+', " "));
+        String::from(encoding.decode_without_bom_handling(&bytes).0)
+    };
+    input
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(name), decode(value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::form_urlencoded::{serialize, Serializer, Spaces};
+    use crate::percent::EncodeSet;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn should_match_url_search_params() {
+        use crate::UrlSearchParams;
+
+        let pairs = [
+            ("a b", "c+d"),
+            ("", ""),
+            ("~!'()*", "-._"),
+            ("日本", "&=%#/?"),
+        ];
+        let params: UrlSearchParams = pairs.into_iter().collect();
+        assert_eq!(serialize(pairs), params.to_string());
+    }
+
+    #[test]
+    fn should_escape_spaces_as_configured() {
+        assert_eq!(serialize::<&str, _>([]), "");
+
+        let percent = Serializer {
+            spaces: Spaces::Percent,
+            ..Serializer::default()
+        };
+        assert_eq!(percent.serialize([("a b", "c+d ")]), "a%20b=c%2Bd%20");
+
+        // `+` stays unambiguous even with a set that does not encode it.
+        let plus = Serializer {
+            spaces: Spaces::Plus,
+            encode_set: EncodeSet::Query,
+        };
+        assert_eq!(plus.serialize([("a b", "c+d")]), "a+b=c%2Bd");
+
+        let query = Serializer {
+            spaces: Spaces::Percent,
+            encode_set: EncodeSet::Query,
+        };
+        assert_eq!(query.serialize([("a b", "c+d/é")]), "a%20b=c+d/%C3%A9");
+    }
+
+    #[test]
+    fn should_parse_borrowing_when_possible() {
+        use crate::form_urlencoded::parse;
+        use alloc::borrow::Cow;
+        use alloc::vec::Vec;
+
+        let pairs: Vec<_> = parse("a=1&=&b&c+d=%zz&e=%C3%A9=").collect();
+        assert_eq!(
+            pairs,
+            [("a", "1"), ("", ""), ("b", ""), ("c d", "%zz"), ("e", "é="),]
+                .map(|(name, value)| (Cow::from(name), Cow::from(value)))
+        );
+        assert!(matches!(pairs[0], (Cow::Borrowed(_), Cow::Borrowed(_))));
+        assert!(matches!(pairs[3], (Cow::Owned(_), Cow::Owned(_))));
+        assert_eq!(parse("&&").count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "encoding")]
+    fn should_serialize_and_parse_with_encoding() {
+        use crate::form_urlencoded::parse_with_encoding;
+        use alloc::string::ToString;
+        use encoding_rs::{SHIFT_JIS, UTF_8, WINDOWS_1252};
+
+        let pairs = [("名前", "日本 +"), ("x", "é")];
+        let serialized = Serializer::default().serialize_with_encoding(pairs, SHIFT_JIS);
+        assert_eq!(serialized, "%96%BC%91O=%93%FA%96%7B+%2B&x=%26%23233%3B");
+        assert_eq!(
+            parse_with_encoding(&serialized, SHIFT_JIS),
+            [
+                ("名前".to_string(), "日本 +".to_string()),
+                ("x".to_string(), "&#233;".to_string())
+            ]
+        );
+        assert_eq!(
+            Serializer::default().serialize_with_encoding(pairs, UTF_8),
+            serialize(pairs)
+        );
+        let percent = Serializer {
+            spaces: Spaces::Percent,
+            encode_set: EncodeSet::Query,
+        };
+        assert_eq!(
+            percent.serialize_with_encoding([("a b", "é+")], WINDOWS_1252),
+            "a%20b=%E9+"
+        );
+        assert_eq!(
+            parse_with_encoding("%E9=%FF%zz", WINDOWS_1252),
+            [("é".to_string(), "ÿ%zz".to_string())]
+        );
+    }
+}
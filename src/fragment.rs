@@ -0,0 +1,413 @@
+//! Parsing of the directives carried in the fragment of a URL, such as the
+//! [text fragments](https://wicg.github.io/scroll-to-text-fragment/) of `#:~:text=...` and the
+//! [media fragments](https://www.w3.org/TR/media-frags/) of `#t=10,20`.
+//!
+//! This module is only available if the `alloc` Cargo feature is enabled.
+//!
+//! ```
+//! use ada_url::Url;
+//!
+//! let url = Url::parse("https://example.com/#intro:~:text=an%20example,-text", None)
+//!     .expect("Invalid URL");
+//! let directive = url.fragment_directive();
+//! assert_eq!(directive.fragment, "intro");
+//! let text = directive.text_directives().next().unwrap();
+//! assert_eq!(text.start, "an example");
+//! assert_eq!(text.suffix.as_deref(), Some("text"));
+//! ```
+
+use crate::percent;
+use alloc::string::String;
+
+/// The delimiter between the fragment and the fragment directive.
+const DELIMITER: &str = ":~:";
+
+/// A fragment split into the fragment used to navigate and the
+/// [fragment directive](https://wicg.github.io/scroll-to-text-fragment/#fragment-directive)
+/// after the `:~:` delimiter, which browsers remove from the fragment exposed to pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FragmentDirective<'a> {
+    /// The fragment before the delimiter, or the whole fragment if there is no delimiter.
+    pub fragment: &'a str,
+    /// The directives after the delimiter, separated by `&`, or `None` if there is no
+    /// delimiter.
+    pub directives: Option<&'a str>,
+}
+
+impl<'a> FragmentDirective<'a> {
+    /// Splits a fragment, with or without its leading `#`, at the first `:~:` delimiter.
+    ///
+    /// ```
+    /// use ada_url::fragment::FragmentDirective;
+    ///
+    /// let directive = FragmentDirective::parse("#top:~:text=a&note=b");
+    /// assert_eq!(directive.fragment, "top");
+    /// assert_eq!(directive.directives, Some("text=a&note=b"));
+    /// assert_eq!(FragmentDirective::parse("top").directives, None);
+    /// ```
+    #[must_use]
+    pub fn parse(fragment: &'a str) -> Self {
+        let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+        match fragment.split_once(DELIMITER) {
+            Some((fragment, directives)) => Self {
+                fragment,
+                directives: Some(directives),
+            },
+            None => Self {
+                fragment,
+                directives: None,
+            },
+        }
+    }
+
+    /// Returns an iterator over the valid `text=` directives, skipping the other directives
+    /// and the malformed text directives.
+    pub fn text_directives(&self) -> impl Iterator<Item = TextDirective> + 'a {
+        self.directives
+            .unwrap_or_default()
+            .split('&')
+            .filter_map(|directive| directive.strip_prefix("text="))
+            .filter_map(TextDirective::parse)
+    }
+}
+
+/// A [text directive](https://wicg.github.io/scroll-to-text-fragment/#text-directives), i.e.
+/// `text=[prefix-,]start[,end][,-suffix]`, with its terms percent-decoded.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextDirective {
+    /// The text which must immediately precede the match.
+    pub prefix: Option<String>,
+    /// The text to match, or the start of the range to match if `end` is set.
+    pub start: String,
+    /// The end of the range to match.
+    pub end: Option<String>,
+    /// The text which must immediately follow the match.
+    pub suffix: Option<String>,
+}
+
+impl TextDirective {
+    /// Parses the value of a `text=` directive, or returns `None` if it is malformed.
+    ///
+    /// ```
+    /// use ada_url::fragment::TextDirective;
+    ///
+    /// let text = TextDirective::parse("an-,example,text%2C%20end,-here").unwrap();
+    /// assert_eq!(text.prefix.as_deref(), Some("an"));
+    /// assert_eq!(text.start, "example");
+    /// assert_eq!(text.end.as_deref(), Some("text, end"));
+    /// assert_eq!(text.suffix.as_deref(), Some("here"));
+    /// assert_eq!(TextDirective::parse("a-,-b"), None);
+    /// ```
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut terms: alloc::vec::Vec<&str> = value.split(',').collect();
+        if terms.len() > 4 {
+            return None;
+        }
+        let prefix = match terms.first() {
+            Some(term) if terms.len() > 1 && term.ends_with('-') => {
+                let prefix = terms.remove(0);
+                Some(&prefix[..prefix.len() - 1])
+            }
+            _ => None,
+        };
+        let suffix = match terms.last() {
+            Some(term) if terms.len() > 1 && term.starts_with('-') => {
+                terms.pop().map(|suffix| &suffix[1..])
+            }
+            _ => None,
+        };
+        let (start, end) = match terms[..] {
+            [start] => (start, None),
+            [start, end] => (start, Some(end)),
+            _ => return None,
+        };
+        let terms = [prefix, Some(start), end, suffix];
+        if terms
+            .iter()
+            .flatten()
+            .any(|term| term.is_empty() || term.starts_with('-') || term.ends_with('-'))
+        {
+            return None;
+        }
+        Some(Self {
+            prefix: prefix.map(decode),
+            start: decode(start),
+            end: end.map(decode),
+            suffix: suffix.map(decode),
+        })
+    }
+}
+
+/// The temporal and spatial dimensions of a [media fragment](https://www.w3.org/TR/media-frags/),
+/// e.g. `#t=10,20&xywh=percent:25,25,50,50`.
+///
+/// As in the specification, a dimension which is invalid is ignored, and the last valid
+/// occurrence of a repeated dimension is used.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MediaFragment {
+    /// The time range of the `t` dimension.
+    pub temporal: Option<TemporalFragment>,
+    /// The clip rectangle of the `xywh` dimension.
+    pub spatial: Option<SpatialFragment>,
+}
+
+/// A time range of a media fragment, in seconds. Only the normal play time format, e.g. `10.5`,
+/// `npt:1:30` or `0:01:30`, is supported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemporalFragment {
+    /// The start of the range, which is 0 when omitted, as in `t=,20`.
+    pub start: f64,
+    /// The end of the range, or `None` for the end of the media.
+    pub end: Option<f64>,
+}
+
+/// A clip rectangle of a media fragment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpatialFragment {
+    /// Whether the coordinates are in pixels or percents of the media dimensions.
+    pub unit: SpatialUnit,
+    /// The horizontal offset of the rectangle.
+    pub x: u32,
+    /// The vertical offset of the rectangle.
+    pub y: u32,
+    /// The width of the rectangle, which is not 0.
+    pub width: u32,
+    /// The height of the rectangle, which is not 0.
+    pub height: u32,
+}
+
+/// The unit of a [`SpatialFragment`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SpatialUnit {
+    /// Pixels, the default, as in `xywh=10,10,50,50` or `xywh=pixel:10,10,50,50`.
+    #[default]
+    Pixel,
+    /// Percents, as in `xywh=percent:25,25,50,50`.
+    Percent,
+}
+
+impl MediaFragment {
+    /// Parses the media fragment dimensions of a fragment, with or without its leading `#`.
+    ///
+    /// ```
+    /// use ada_url::fragment::{MediaFragment, SpatialFragment, SpatialUnit, TemporalFragment};
+    ///
+    /// let media = MediaFragment::parse("#t=npt:01:30,100.5&xywh=percent:25,25,50,50");
+    /// assert_eq!(media.temporal, Some(TemporalFragment { start: 90.0, end: Some(100.5) }));
+    /// assert_eq!(
+    ///     media.spatial,
+    ///     Some(SpatialFragment { unit: SpatialUnit::Percent, x: 25, y: 25, width: 50, height: 50 })
+    /// );
+    /// assert_eq!(MediaFragment::parse("t=20,10"), MediaFragment::default());
+    /// ```
+    #[must_use]
+    pub fn parse(fragment: &str) -> Self {
+        let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+        let mut media = Self::default();
+        for pair in fragment.split('&') {
+            let Some((name, value)) = pair.split_once('=') else {
+                continue;
+            };
+            let (name, value) = (decode(name), decode(value));
+            match name.as_str() {
+                "t" => {
+                    if let Some(temporal) = TemporalFragment::parse(&value) {
+                        media.temporal = Some(temporal);
+                    }
+                }
+                "xywh" => {
+                    if let Some(spatial) = SpatialFragment::parse(&value) {
+                        media.spatial = Some(spatial);
+                    }
+                }
+                _ => {}
+            }
+        }
+        media
+    }
+}
+
+impl TemporalFragment {
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.strip_prefix("npt:").unwrap_or(value);
+        let (start, end) = match value.split_once(',') {
+            Some(("", end)) => (0.0, Some(parse_npt_time(end)?)),
+            Some((start, end)) => (parse_npt_time(start)?, Some(parse_npt_time(end)?)),
+            None => (parse_npt_time(value)?, None),
+        };
+        if end.is_some_and(|end| end <= start) {
+            return None;
+        }
+        Some(Self { start, end })
+    }
+}
+
+/// Parses `seconds[.fraction]`, `mm:ss[.fraction]` or `hh:mm:ss[.fraction]`.
+fn parse_npt_time(input: &str) -> Option<f64> {
+    let is_seconds = |seconds: &str| {
+        let (whole, fraction) = seconds.split_once('.').unwrap_or((seconds, "0"));
+        !whole.is_empty()
+            && whole.bytes().all(|byte| byte.is_ascii_digit())
+            && fraction.bytes().all(|byte| byte.is_ascii_digit())
+    };
+    let is_two_digits = |input: &str| {
+        input.len() == 2 && input.bytes().all(|byte| byte.is_ascii_digit()) && input < "60"
+    };
+    let parts: alloc::vec::Vec<&str> = input.split(':').collect();
+    let (hours, minutes, seconds) = match parts[..] {
+        [seconds] => ("0", "0", seconds),
+        [minutes, seconds] => ("0", minutes, seconds),
+        [hours, minutes, seconds] => (hours, minutes, seconds),
+        _ => return None,
+    };
+    if !is_seconds(seconds)
+        || (parts.len() > 1
+            && !(is_two_digits(minutes) && seconds.get(..2).is_some_and(is_two_digits)))
+        || (parts.len() > 1 && seconds.as_bytes().get(2).is_some_and(|&byte| byte != b'.'))
+        || !hours.bytes().all(|byte| byte.is_ascii_digit())
+        || !minutes.bytes().all(|byte| byte.is_ascii_digit())
+    {
+        return None;
+    }
+    let hours: f64 = hours.parse().ok()?;
+    let minutes: f64 = minutes.parse().ok()?;
+    let seconds: f64 = seconds.parse().ok()?;
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+impl SpatialFragment {
+    fn parse(value: &str) -> Option<Self> {
+        let (unit, value) = if let Some(value) = value.strip_prefix("percent:") {
+            (SpatialUnit::Percent, value)
+        } else {
+            (
+                SpatialUnit::Pixel,
+                value.strip_prefix("pixel:").unwrap_or(value),
+            )
+        };
+        let mut numbers = value.split(',').map(|number| {
+            if number.is_empty() || !number.bytes().all(|byte| byte.is_ascii_digit()) {
+                return None;
+            }
+            number.parse::<u32>().ok()
+        });
+        let mut next = || numbers.next().flatten();
+        let (x, y, width, height) = (next()?, next()?, next()?, next()?);
+        if numbers.next().is_some() || width == 0 || height == 0 {
+            return None;
+        }
+        if unit == SpatialUnit::Percent
+            && (x.checked_add(width)? > 100 || y.checked_add(height)? > 100)
+        {
+            return None;
+        }
+        Some(Self {
+            unit,
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+}
+
+fn decode(input: &str) -> String {
+    String::from_utf8_lossy(&percent::decode(input)).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fragment::{FragmentDirective, TextDirective};
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn should_parse_text_directives() {
+        let cases = [
+            ("a", Some((None, "a", None, None))),
+            ("a,b", Some((None, "a", Some("b"), None))),
+            ("p-,a", Some((Some("p"), "a", None, None))),
+            ("a,-s", Some((None, "a", None, Some("s")))),
+            ("p-,a,b,-s", Some((Some("p"), "a", Some("b"), Some("s")))),
+            ("%2D-,%E2%9C%93", Some((Some("-"), "✓", None, None))),
+            ("", None),
+            ("p-", None),
+            ("-s", None),
+            ("p-,-s", None),
+            ("a,,b", None),
+            ("a,b,c", None),
+            ("p-,a,b,c,-s", None),
+        ];
+        for (input, expected) in cases {
+            let expected = expected.map(|(prefix, start, end, suffix)| TextDirective {
+                prefix: prefix.map(ToString::to_string),
+                start: start.to_string(),
+                end: end.map(ToString::to_string),
+                suffix: suffix.map(ToString::to_string),
+            });
+            assert_eq!(TextDirective::parse(input), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn should_parse_media_fragments() {
+        use crate::fragment::{MediaFragment, SpatialFragment, SpatialUnit, TemporalFragment};
+
+        let temporal = |input| MediaFragment::parse(input).temporal;
+        let range = |start, end| Some(TemporalFragment { start, end });
+        assert_eq!(temporal("t=10"), range(10.0, None));
+        assert_eq!(temporal("t=10,20"), range(10.0, Some(20.0)));
+        assert_eq!(temporal("t=,20"), range(0.0, Some(20.0)));
+        assert_eq!(temporal("t=npt:.5"), None);
+        assert_eq!(temporal("t=npt:0.5,1:00:01.5"), range(0.5, Some(3601.5)));
+        assert_eq!(temporal("t=01:30"), range(90.0, None));
+        assert_eq!(temporal("t%3D=1%2C2"), None);
+        assert_eq!(temporal("t=1%2C2"), range(1.0, Some(2.0)));
+        assert_eq!(temporal("t=1:60"), None);
+        assert_eq!(temporal("t=1:5"), None);
+        assert_eq!(temporal("t=01:5"), None);
+        assert_eq!(temporal("t=01:5.5"), None);
+        assert_eq!(temporal("t=10,"), None);
+        assert_eq!(temporal("t=10,10"), None);
+        assert_eq!(temporal("t=smpte:0:02:00"), None);
+        // The last valid occurrence wins.
+        assert_eq!(temporal("t=1&t=2&t=x"), range(2.0, None));
+
+        let spatial = |input| MediaFragment::parse(input).spatial;
+        let rect = |unit, x, y, width, height| {
+            Some(SpatialFragment {
+                unit,
+                x,
+                y,
+                width,
+                height,
+            })
+        };
+        assert_eq!(
+            spatial("xywh=160,120,320,240"),
+            rect(SpatialUnit::Pixel, 160, 120, 320, 240)
+        );
+        assert_eq!(
+            spatial("xywh=pixel:0,0,1,1"),
+            rect(SpatialUnit::Pixel, 0, 0, 1, 1)
+        );
+        assert_eq!(
+            spatial("xywh=percent:50,50,50,50"),
+            rect(SpatialUnit::Percent, 50, 50, 50, 50)
+        );
+        assert_eq!(spatial("xywh=percent:50,50,51,50"), None);
+        assert_eq!(spatial("xywh=0,0,0,1"), None);
+        assert_eq!(spatial("xywh=1,2,3"), None);
+        assert_eq!(spatial("xywh=1,2,3,4,5"), None);
+        assert_eq!(spatial("xywh=+1,2,3,4"), None);
+    }
+
+    #[test]
+    fn should_skip_other_directives() {
+        let directive = FragmentDirective::parse(":~:text=a&note=b&text=&text=c:~:d");
+        assert_eq!(directive.fragment, "");
+        let starts: Vec<_> = directive.text_directives().map(|text| text.start).collect();
+        assert_eq!(starts, ["a", "c:~:d"]);
+    }
+}
@@ -0,0 +1,133 @@
+//! Building blocks of the URL generators of the `arbitrary`, `proptest` and `quickcheck`
+//! integrations, so that they all exercise the same schemes, characters and hosts.
+
+#[cfg(any(feature = "arbitrary", feature = "quickcheck"))]
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+pub(crate) const SPECIAL_SCHEMES: &[&str] = &["http", "https", "ws", "wss", "ftp"];
+#[cfg(feature = "arbitrary")]
+pub(crate) const OPAQUE_SCHEMES: &[&str] = &["mailto", "data", "javascript", "urn", "tel"];
+#[cfg(feature = "arbitrary")]
+pub(crate) const HIERARCHICAL_SCHEMES: &[&str] = &["git+ssh", "custom", "s3", "blob"];
+
+/// Characters that are valid in most components.
+#[cfg(any(feature = "arbitrary", feature = "quickcheck"))]
+const UNRESERVED: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-._~";
+/// Characters that Ada percent-encodes, normalizes or treats specially in some components.
+#[cfg(any(feature = "arbitrary", feature = "quickcheck"))]
+const INTERESTING: &[&str] = &[
+    "%20", "%2F", "%zz", " ", "!", "$", "&", "'", "(", ")", "*", "+", ",", ";", "=", ":", "@", "/",
+    "\\", "..", ".", "%2e", "|", "^", "{", "}", "\"", "<", ">", "`", "é", "ß", "日本", "😀",
+    "\u{200b}", "\t",
+];
+/// Labels that are mapped or converted to Punycode by the host parser.
+pub(crate) const IDN_LABELS: &[&str] = &[
+    "münchen",
+    "bücher",
+    "日本語",
+    "xn--nxasmq6b",
+    "пример",
+    "ПРИМЕР",
+    "faß",
+    "ñandú",
+    "😀",
+];
+
+/// A source of randomness, implemented for `arbitrary::Unstructured` and `quickcheck::Gen`.
+#[cfg(any(feature = "arbitrary", feature = "quickcheck"))]
+pub(crate) trait Source {
+    type Error;
+
+    /// Returns an index in `0..len`, where `len` is not zero.
+    fn index(&mut self, len: usize) -> Result<usize, Self::Error>;
+
+    /// Returns `true` with a probability of one in `n`.
+    fn one_in(&mut self, n: u8) -> Result<bool, Self::Error>;
+
+    fn u16(&mut self) -> Result<u16, Self::Error>;
+
+    fn u32(&mut self) -> Result<u32, Self::Error>;
+
+    fn u128(&mut self) -> Result<u128, Self::Error>;
+
+    fn pick<'a>(&mut self, choices: &[&'a str]) -> Result<&'a str, Self::Error> {
+        Ok(choices[self.index(choices.len())?])
+    }
+}
+
+/// Generates a string of unreserved characters sprinkled with interesting ones.
+#[cfg(any(feature = "arbitrary", feature = "quickcheck"))]
+pub(crate) fn text<S: Source>(s: &mut S, max_len: usize) -> Result<String, S::Error> {
+    let len = s.index(max_len + 1)?;
+    let mut out = String::with_capacity(len);
+    for _ in 0..len {
+        if s.one_in(8)? {
+            out.push_str(s.pick(INTERESTING)?);
+        } else {
+            let index = s.index(UNRESERVED.len())?;
+            out.push_str(&UNRESERVED[index..=index]);
+        }
+    }
+    Ok(out)
+}
+
+/// Generates a domain label, either internationalized or made of lowercase letters and digits.
+#[cfg(any(feature = "arbitrary", feature = "quickcheck"))]
+pub(crate) fn label<S: Source>(s: &mut S) -> Result<String, S::Error> {
+    if s.one_in(6)? {
+        return s.pick(IDN_LABELS).map(str::to_owned);
+    }
+    let len = 1 + s.index(12)?;
+    let mut out = String::with_capacity(len);
+    for _ in 0..len {
+        let index = s.index(36)?;
+        out.push_str(&UNRESERVED[26 + index..=26 + index].to_ascii_lowercase());
+    }
+    Ok(out)
+}
+
+/// Generates an IPv4, hexadecimal IPv4 or IPv6 host, or a domain of up to four labels.
+#[cfg(any(feature = "arbitrary", feature = "quickcheck"))]
+pub(crate) fn host<S: Source>(s: &mut S) -> Result<String, S::Error> {
+    Ok(match s.index(6)? {
+        0 => Ipv4Addr::from(s.u32()?).to_string(),
+        // Hosts that look like numbers are parsed as IPv4 addresses, e.g. `0x7f.1`.
+        1 => format!("0x{:x}.{}", s.index(256)?, s.u16()?),
+        2 => format!("[{}]", Ipv6Addr::from(s.u128()?)),
+        _ => {
+            let labels = 1 + s.index(4)?;
+            let mut out = label(s)?;
+            for _ in 1..labels {
+                out.push('.');
+                out.push_str(&label(s)?);
+            }
+            out
+        }
+    })
+}
+
+/// Generates a path of up to five segments, which is either empty or starts with `/`.
+#[cfg(any(feature = "arbitrary", feature = "quickcheck"))]
+pub(crate) fn path<S: Source>(s: &mut S) -> Result<String, S::Error> {
+    let segments = s.index(6)?;
+    let mut out = String::new();
+    for _ in 0..segments {
+        out.push('/');
+        out.push_str(&text(s, 10)?);
+    }
+    Ok(out)
+}
+
+/// Appends an optional query and fragment.
+#[cfg(any(feature = "arbitrary", feature = "quickcheck"))]
+pub(crate) fn query_and_fragment<S: Source>(s: &mut S, out: &mut String) -> Result<(), S::Error> {
+    if s.one_in(2)? {
+        out.push('?');
+        out.push_str(&text(s, 20)?);
+    }
+    if s.one_in(2)? {
+        out.push('#');
+        out.push_str(&text(s, 10)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,229 @@
+use crate::{ffi, HostType};
+use alloc::string::String;
+use core::{ffi::c_uint, fmt, hash};
+
+/// Error type of [`Host::parse`].
+#[derive(Debug, derive_more::Display, PartialEq, Eq, derive_more::Error)]
+#[display(bound(Input: core::fmt::Debug))]
+#[display("Invalid host: {input:?}")]
+pub struct ParseHostError<Input> {
+    /// The invalid input that caused the error.
+    pub input: Input,
+}
+
+/// A host and optional port, parsed on its own with the
+/// [host parser](https://url.spec.whatwg.org/#host-parsing) of the URL Standard.
+///
+/// The host of a special URL is converted to ASCII with IDNA and validated, or parsed as an
+/// IPv4 or IPv6 address. The host of a non-special URL is percent-encoded as an opaque host,
+/// unless it is an IPv6 address.
+///
+/// This type is only available if the `alloc` Cargo feature is enabled.
+///
+/// ```
+/// use ada_url::{Host, HostType};
+///
+/// let host = Host::parse("exämple.com:8080", true).expect("Invalid host");
+/// assert_eq!(host.hostname(), "xn--exmple-cua.com");
+/// assert_eq!(host.host_type(), HostType::Domain);
+/// assert_eq!(host.port(), Some(8080));
+/// assert_eq!(host.to_string(), "xn--exmple-cua.com:8080");
+///
+/// assert_eq!(Host::parse("0x7f.1", true).unwrap().hostname(), "127.0.0.1");
+/// assert_eq!(Host::parse("0x7f.1", false).unwrap().host_type(), HostType::Opaque);
+/// assert!(Host::parse("exa mple.com", true).is_err());
+/// assert!(Host::parse("example.com/path", true).is_err());
+/// ```
+#[derive(Clone)]
+pub struct Host {
+    hostname: String,
+    host_type: HostType,
+    port: Option<u16>,
+}
+
+impl Host {
+    /// Parses a host with an optional port, as a host of a special URL such as `https:` if
+    /// `is_special` is true, and as a host of a non-special URL otherwise.
+    ///
+    /// Unlike the URL parser, the whole input must be a host and port, so userinfo, paths,
+    /// queries and fragments are rejected, as are tabs and newlines. An empty host is only valid
+    /// for non-special URLs, and never with a port.
+    pub fn parse<Input>(input: Input, is_special: bool) -> Result<Self, ParseHostError<Input>>
+    where
+        Input: AsRef<str>,
+    {
+        match Self::parse_str(input.as_ref(), is_special) {
+            Some(host) => Ok(host),
+            None => Err(ParseHostError { input }),
+        }
+    }
+
+    fn parse_str(input: &str, is_special: bool) -> Option<Self> {
+        // The host ends at the first colon which is not within an IPv6 address.
+        let host_end = if input.starts_with('[') {
+            input.find(']').map_or(input.len(), |i| i + 1)
+        } else {
+            input.find(':').unwrap_or(input.len())
+        };
+        let (hostname, port) = input.split_at(host_end);
+        let port = match port.strip_prefix(':') {
+            _ if port.is_empty() => None,
+            Some(_) if hostname.is_empty() => return None,
+            Some("") => None,
+            Some(port) if port.bytes().all(|c| c.is_ascii_digit()) => Some(port.parse().ok()?),
+            _ => return None,
+        };
+        if hostname.is_empty() {
+            return (!is_special).then(|| Self {
+                hostname: String::new(),
+                host_type: HostType::Empty,
+                port,
+            });
+        }
+        let mut out = ffi::ada_owned_string {
+            data: core::ptr::null(),
+            length: 0,
+        };
+        let mut host_type = 0;
+        let is_valid = unsafe {
+            ffi::ada_parse_host(
+                hostname.as_ptr().cast(),
+                hostname.len(),
+                is_special,
+                &mut out,
+                &mut host_type,
+            )
+        };
+        if !is_valid {
+            return None;
+        }
+        // Ada only distinguishes IP addresses from other hosts.
+        let host_type = match HostType::from(c_uint::from(host_type)) {
+            HostType::Domain if !is_special => HostType::Opaque,
+            host_type => host_type,
+        };
+        Some(Self {
+            hostname: String::from(out.as_ref()),
+            host_type,
+            port,
+        })
+    }
+
+    /// Returns the serialized host, without the port, e.g. `xn--exmple-cua.com`, `127.0.0.1`
+    /// or `[::1]`.
+    #[must_use]
+    pub fn hostname(&self) -> &str {
+        &self.hostname
+    }
+
+    /// Returns the type of the host.
+    #[must_use]
+    pub fn host_type(&self) -> HostType {
+        self.host_type.clone()
+    }
+
+    /// Returns the port, if any.
+    ///
+    /// ```
+    /// use ada_url::Host;
+    ///
+    /// assert_eq!(Host::parse("example.com:0080", true).unwrap().port(), Some(80));
+    /// assert_eq!(Host::parse("example.com:", true).unwrap().port(), None);
+    /// assert!(Host::parse("example.com:65536", true).is_err());
+    /// ```
+    #[must_use]
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+}
+
+impl PartialEq for Host {
+    fn eq(&self, other: &Self) -> bool {
+        self.hostname() == other.hostname() && self.port == other.port
+    }
+}
+
+impl Eq for Host {}
+
+impl hash::Hash for Host {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.hostname().hash(state);
+        self.port.hash(state);
+    }
+}
+
+impl fmt::Debug for Host {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Host")
+            .field("hostname", &self.hostname())
+            .field("host_type", &self.host_type)
+            .field("port", &self.port)
+            .finish()
+    }
+}
+
+/// Formats the host as in the `host` of a URL, followed by the port if any.
+impl fmt::Display for Host {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.hostname())?;
+        if let Some(port) = self.port {
+            write!(f, ":{port}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Host, HostType};
+
+    #[test]
+    fn should_parse_hosts() {
+        let cases = [
+            ("EXAMPLE.com", true, "example.com", HostType::Domain, None),
+            ("127.1:1", true, "127.0.0.1", HostType::IPV4, Some(1)),
+            ("[0:0::1]:443", true, "[::1]", HostType::IPV6, Some(443)),
+            ("[::1]", false, "[::1]", HostType::IPV6, None),
+            ("é.com", false, "%C3%A9.com", HostType::Opaque, None),
+            ("A.com:8", false, "A.com", HostType::Opaque, Some(8)),
+            ("%41.com", true, "a.com", HostType::Domain, None),
+            ("1.0x10.", true, "1.0.0.16", HostType::IPV4, None),
+            ("a.0xg", true, "a.0xg", HostType::Domain, None),
+            ("", false, "", HostType::Empty, None),
+        ];
+        for (input, is_special, hostname, host_type, port) in cases {
+            let host = Host::parse(input, is_special).unwrap();
+            assert_eq!(host.hostname(), hostname, "{input}");
+            assert_eq!(host.host_type(), host_type, "{input}");
+            assert_eq!(host.port(), port, "{input}");
+        }
+    }
+
+    #[test]
+    fn should_reject_invalid_hosts() {
+        let cases = [
+            ("", true),
+            (":80", false),
+            (":", false),
+            ("a:b", true),
+            ("a:-1", true),
+            ("[::1", true),
+            ("[::1]x", true),
+            ("[::1]:x", false),
+            ("user@example.com", true),
+            ("example.com?", false),
+            ("example.com#", false),
+            ("a\\b", true),
+            ("a\tb", true),
+            (" example.com", true),
+            ("example.com\0", false),
+            ("exa<mple", false),
+            ("999.1.1.1", true),
+            ("example.0x", true),
+            ("xn--a.com", true),
+        ];
+        for (input, is_special) in cases {
+            assert!(Host::parse(input, is_special).is_err(), "{input:?}");
+        }
+    }
+}
@@ -1,11 +1,101 @@
-#[cfg(feature = "std")]
-extern crate std;
-
-#[cfg_attr(not(feature = "std"), allow(unused_imports))]
+#[cfg_attr(not(feature = "alloc"), allow(unused_imports))]
 use crate::ffi;
 
-#[cfg(feature = "std")]
-use std::string::String;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+/// Error type of [`Idna::try_ascii`], [`Idna::try_unicode`], [`Idna::to_ascii_with`] and
+/// [`Idna::to_unicode_with`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+#[display("Invalid domain label: {label:?}")]
+pub struct IdnaError {
+    /// The first label rejected by UTS #46 processing, or the whole input if no single label
+    /// is invalid on its own.
+    pub label: String,
+}
+
+/// Processing options of [`Idna::to_ascii_with`] and [`Idna::to_unicode_with`], named after the
+/// flags of UTS #46.
+///
+/// The default options are the ones used by the URL Standard to process hosts. Use
+/// [`IdnaOptions::strict`] to validate domain names for registration.
+///
+/// For more information, [read the specification](https://www.unicode.org/reports/tr46/#Processing)
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IdnaOptions {
+    /// Apply the STD3 ASCII rules (`UseSTD3ASCIIRules`), only allowing letters, digits and
+    /// hyphens among ASCII characters.
+    pub be_strict: bool,
+    /// Reject labels beginning or ending with a hyphen, or with hyphens in the third and
+    /// fourth positions (`CheckHyphens`).
+    pub check_hyphens: bool,
+    /// Apply the Bidi rule of RFC 5893 to right-to-left labels (`CheckBidi`).
+    ///
+    /// Ada stops validating a label once it has checked its first joiner, U+200C or U+200D
+    /// (`CheckJoiners`), so labels containing a joiner are never checked against the Bidi rule,
+    /// even if this is true.
+    pub check_bidi: bool,
+    /// Reject empty domains, empty labels other than the root label, labels longer than 63
+    /// bytes and domains longer than 253 bytes (`VerifyDnsLength`).
+    pub verify_dns_length: bool,
+    /// Use transitional processing, mapping deviation characters such as `ß` to their IDNA2003
+    /// equivalent instead of keeping them (`Transitional_Processing`).
+    pub transitional: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl IdnaOptions {
+    /// Options enabling every check with nontransitional processing, suitable to validate domain
+    /// names before registration.
+    ///
+    /// ```
+    /// use ada_url::{Idna, IdnaOptions};
+    /// assert!(Idna::to_ascii_with("my_domain.com", IdnaOptions::default()).is_ok());
+    /// assert!(Idna::to_ascii_with("my_domain.com", IdnaOptions::strict()).is_err());
+    /// ```
+    #[must_use]
+    pub const fn strict() -> Self {
+        Self {
+            be_strict: true,
+            check_hyphens: true,
+            check_bidi: true,
+            verify_dns_length: true,
+            transitional: false,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Default for IdnaOptions {
+    fn default() -> Self {
+        Self {
+            be_strict: false,
+            check_hyphens: false,
+            check_bidi: true,
+            verify_dns_length: false,
+            transitional: false,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<IdnaOptions> for ffi::ada_idna_options {
+    fn from(options: IdnaOptions) -> Self {
+        Self {
+            be_strict: options.be_strict,
+            check_hyphens: options.check_hyphens,
+            check_bidi: options.check_bidi,
+            verify_dns_length: options.verify_dns_length,
+            transitional: options.transitional,
+        }
+    }
+}
+
+/// Label separators recognized by UTS #46, which maps all of them to `.`.
+#[cfg(feature = "alloc")]
+const LABEL_SEPARATORS: [char; 4] = ['.', '\u{3002}', '\u{ff0e}', '\u{ff61}'];
 
 /// IDNA struct implements the `to_ascii` and `to_unicode` functions from the Unicode Technical
 /// Standard supporting a wide range of systems. It is suitable for URL parsing.
@@ -19,13 +109,18 @@ impl Idna {
     /// For more information, [read the specification](https://www.unicode.org/reports/tr46/#ToUnicode)
     ///
     /// ```
+    /// # #![allow(deprecated)]
     /// use ada_url::Idna;
     /// assert_eq!(Idna::unicode("xn--meagefactory-m9a.ca"), "meßagefactory.ca");
     /// ```
     #[must_use]
-    #[cfg(feature = "std")]
+    #[cfg(feature = "alloc")]
+    #[deprecated(
+        since = "3.2.0",
+        note = "use `Idna::try_unicode`, which reports invalid input instead of ignoring it"
+    )]
     pub fn unicode(input: &str) -> String {
-        unsafe { ffi::ada_idna_to_unicode(input.as_ptr().cast(), input.len()) }.to_string()
+        to_unicode(input)
     }
 
     /// Process international domains according to the UTS #46 standard.
@@ -34,30 +129,441 @@ impl Idna {
     /// For more information, [read the specification](https://www.unicode.org/reports/tr46/#ToASCII)
     ///
     /// ```
+    /// # #![allow(deprecated)]
     /// use ada_url::Idna;
     /// assert_eq!(Idna::ascii("meßagefactory.ca"), "xn--meagefactory-m9a.ca");
     /// ```
     #[must_use]
-    #[cfg(feature = "std")]
+    #[cfg(feature = "alloc")]
+    #[deprecated(
+        since = "3.2.0",
+        note = "use `Idna::try_ascii`, which returns an error instead of an empty string"
+    )]
     pub fn ascii(input: &str) -> String {
-        unsafe { ffi::ada_idna_to_ascii(input.as_ptr().cast(), input.len()) }.to_string()
+        to_ascii(input)
+    }
+
+    /// Process international domains according to the UTS #46 standard.
+    /// Returns an error holding the first invalid label if the input is invalid.
+    ///
+    /// For more information, [read the specification](https://www.unicode.org/reports/tr46/#ToASCII)
+    ///
+    /// ```
+    /// use ada_url::Idna;
+    /// assert_eq!(Idna::try_ascii("meßagefactory.ca").unwrap(), "xn--meagefactory-m9a.ca");
+    /// assert_eq!(Idna::try_ascii("xn--zz.example.com").unwrap_err().label, "xn--zz");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn try_ascii(input: &str) -> Result<String, IdnaError> {
+        let output = to_ascii(input);
+        if output.is_empty() && !input.is_empty() {
+            return Err(invalid_label(input, |label| !to_ascii(label).is_empty()));
+        }
+        Ok(output)
+    }
+
+    /// Process international domains according to the UTS #46 standard.
+    /// Returns an error holding the first invalid label if the input is invalid, e.g. if a
+    /// label starting with `xn--` is not valid Punycode.
+    ///
+    /// For more information, [read the specification](https://www.unicode.org/reports/tr46/#ToUnicode)
+    ///
+    /// ```
+    /// use ada_url::Idna;
+    /// assert_eq!(Idna::try_unicode("xn--meagefactory-m9a.ca").unwrap(), "meßagefactory.ca");
+    /// assert_eq!(Idna::try_unicode("xn--zz.example.com").unwrap_err().label, "xn--zz");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn try_unicode(input: &str) -> Result<String, IdnaError> {
+        // ToUnicode reports the same errors as ToASCII, but Ada's implementation keeps invalid
+        // labels as is instead of reporting them.
+        if !input.is_empty() && to_ascii(input).is_empty() {
+            return Err(invalid_label(input, |label| !to_ascii(label).is_empty()));
+        }
+        Ok(to_unicode(input))
+    }
+
+    /// Process international domains according to the UTS #46 standard with the given options.
+    /// Returns an error holding the first invalid label if the input is invalid.
+    ///
+    /// For more information, [read the specification](https://www.unicode.org/reports/tr46/#ToASCII)
+    ///
+    /// ```
+    /// use ada_url::{Idna, IdnaOptions};
+    /// let options = IdnaOptions {
+    ///     transitional: true,
+    ///     ..IdnaOptions::default()
+    /// };
+    /// assert_eq!(Idna::to_ascii_with("meßagefactory.ca", options).unwrap(), "messagefactory.ca");
+    /// assert_eq!(
+    ///     Idna::to_ascii_with("-foo.example.com", IdnaOptions::strict()).unwrap_err().label,
+    ///     "-foo"
+    /// );
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_ascii_with(input: &str, options: IdnaOptions) -> Result<String, IdnaError> {
+        to_ascii_with_options(input, options).ok_or_else(|| {
+            invalid_label(input, |label| {
+                to_ascii_with_options(label, options).is_some()
+            })
+        })
+    }
+
+    /// Process international domains according to the UTS #46 standard with the given options.
+    /// Returns an error holding the first invalid label if the input is invalid. Unlike
+    /// [`Idna::try_unicode`], labels are also mapped, e.g. to lower case.
+    ///
+    /// For more information, [read the specification](https://www.unicode.org/reports/tr46/#ToUnicode)
+    ///
+    /// ```
+    /// use ada_url::{Idna, IdnaOptions};
+    /// assert_eq!(
+    ///     Idna::to_unicode_with("XN--MEAGEFACTORY-M9A.CA", IdnaOptions::default()).unwrap(),
+    ///     "meßagefactory.ca"
+    /// );
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_unicode_with(input: &str, options: IdnaOptions) -> Result<String, IdnaError> {
+        to_unicode_with_options(input, options).ok_or_else(|| {
+            invalid_label(input, |label| {
+                to_unicode_with_options(label, options).is_some()
+            })
+        })
+    }
+
+    /// Maps the input with the IDNA Mapping Table, the first step of UTS #46 processing, e.g. to
+    /// lower case. Returns `None` if the input contains a disallowed code point.
+    ///
+    /// Together with [`Idna::normalize`] and [`Idna::is_label_valid`], this allows reporting
+    /// which step rejects a domain.
+    ///
+    /// For more information, [read the specification](https://www.unicode.org/reports/tr46/#ProcessingStepMap)
+    ///
+    /// ```
+    /// use ada_url::Idna;
+    /// assert_eq!(Idna::map("ＥＸＡＭＰＬＥ。Com").unwrap(), "example.com");
+    /// assert_eq!(Idna::map("exa\u{ad}mple.com").unwrap(), "example.com");
+    /// assert_eq!(Idna::map("a\u{2028}b.com"), None);
+    /// ```
+    #[must_use]
+    #[cfg(feature = "alloc")]
+    pub fn map(input: &str) -> Option<String> {
+        let mut out = ffi::ada_owned_string {
+            data: core::ptr::null(),
+            length: 0,
+        };
+        unsafe { ffi::ada_idna_map(input.as_ptr().cast(), input.len(), &mut out) }
+            .then(|| String::from(out.as_ref()))
+    }
+
+    /// Normalizes the input to Unicode Normalization Form C, the second step of UTS #46
+    /// processing.
+    ///
+    /// For more information, [read the specification](https://www.unicode.org/reports/tr46/#ProcessingStepNormalize)
+    ///
+    /// ```
+    /// use ada_url::Idna;
+    /// assert_eq!(Idna::normalize("cafe\u{301}.fr"), "caf\u{e9}.fr");
+    /// ```
+    #[must_use]
+    #[cfg(feature = "alloc")]
+    pub fn normalize(input: &str) -> String {
+        String::from(
+            unsafe { ffi::ada_idna_normalize(input.as_ptr().cast(), input.len()) }.as_ref(),
+        )
+    }
+
+    /// Checks the validity criteria of a single mapped and normalized label, as applied when
+    /// processing URLs: the label must not begin with a combining mark and must satisfy the
+    /// `CheckJoiners` and `CheckBidi` rules.
+    ///
+    /// For more information, [read the specification](https://www.unicode.org/reports/tr46/#Validity_Criteria)
+    ///
+    /// ```
+    /// use ada_url::Idna;
+    /// assert!(Idna::is_label_valid("bücher"));
+    /// assert!(!Idna::is_label_valid("\u{301}a"));
+    /// ```
+    #[must_use]
+    pub fn is_label_valid(label: &str) -> bool {
+        unsafe { ffi::ada_idna_is_label_valid(label.as_ptr().cast(), label.len()) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn to_ascii(input: &str) -> String {
+    String::from(unsafe { ffi::ada_idna_to_ascii(input.as_ptr().cast(), input.len()) }.as_ref())
+}
+
+#[cfg(feature = "alloc")]
+fn to_unicode(input: &str) -> String {
+    String::from(unsafe { ffi::ada_idna_to_unicode(input.as_ptr().cast(), input.len()) }.as_ref())
+}
+
+#[cfg(feature = "alloc")]
+fn to_ascii_with_options(input: &str, options: IdnaOptions) -> Option<String> {
+    let mut out = ffi::ada_owned_string {
+        data: core::ptr::null(),
+        length: 0,
+    };
+    unsafe {
+        ffi::ada_idna_to_ascii_with_options(
+            input.as_ptr().cast(),
+            input.len(),
+            options.into(),
+            &mut out,
+        )
+    }
+    .then(|| String::from(out.as_ref()))
+}
+
+#[cfg(feature = "alloc")]
+fn to_unicode_with_options(input: &str, options: IdnaOptions) -> Option<String> {
+    let mut out = ffi::ada_owned_string {
+        data: core::ptr::null(),
+        length: 0,
+    };
+    unsafe {
+        ffi::ada_idna_to_unicode_with_options(
+            input.as_ptr().cast(),
+            input.len(),
+            options.into(),
+            &mut out,
+        )
+    }
+    .then(|| String::from(out.as_ref()))
+}
+
+/// Builds the error for a rejected input, pointing at its first label rejected by `is_valid`.
+#[cfg(feature = "alloc")]
+fn invalid_label(input: &str, is_valid: impl Fn(&str) -> bool) -> IdnaError {
+    let label = input
+        .split(LABEL_SEPARATORS)
+        .find(|label| !label.is_empty() && !is_valid(label))
+        .unwrap_or(input);
+    IdnaError {
+        label: label.into(),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    #[cfg_attr(not(feature = "std"), allow(unused_imports))]
+    #[cfg_attr(not(feature = "alloc"), allow(unused_imports))]
     use crate::idna::*;
 
     #[test]
+    #[allow(deprecated)]
     fn unicode_should_work() {
-        #[cfg(feature = "std")]
+        #[cfg(feature = "alloc")]
         assert_eq!(Idna::unicode("xn--meagefactory-m9a.ca"), "meßagefactory.ca");
     }
 
     #[test]
+    #[allow(deprecated)]
     fn ascii_should_work() {
-        #[cfg(feature = "std")]
+        #[cfg(feature = "alloc")]
         assert_eq!(Idna::ascii("meßagefactory.ca"), "xn--meagefactory-m9a.ca");
     }
+
+    #[test]
+    fn try_ascii_should_report_invalid_label() {
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(
+                Idna::try_ascii("ＥＸＡＭＰＬＥ。com").unwrap(),
+                "example.com"
+            );
+            assert_eq!(Idna::try_ascii("").unwrap(), "");
+            let err = Idna::try_ascii("a.\u{200d}.com").unwrap_err();
+            assert_eq!(err.label, "\u{200d}");
+            let err = Idna::try_ascii("xn--zz.example.com").unwrap_err();
+            assert_eq!(
+                alloc::string::ToString::to_string(&err),
+                "Invalid domain label: \"xn--zz\""
+            );
+        }
+    }
+
+    #[test]
+    fn try_unicode_should_report_invalid_punycode() {
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(Idna::try_unicode("xn--ls8h.la").unwrap(), "💩.la");
+            assert_eq!(
+                Idna::try_unicode("a。xn--a.com").unwrap_err().label,
+                "xn--a"
+            );
+        }
+    }
+
+    #[test]
+    fn to_ascii_with_should_apply_options() {
+        #[cfg(feature = "alloc")]
+        {
+            let default = IdnaOptions::default();
+            let strict = IdnaOptions::strict();
+            assert_eq!(
+                Idna::to_ascii_with("Meßagefactory.CA", default).unwrap(),
+                "xn--meagefactory-m9a.ca"
+            );
+            assert_eq!(
+                Idna::to_ascii_with("Meßagefactory.CA", strict).unwrap(),
+                "xn--meagefactory-m9a.ca"
+            );
+            assert_eq!(
+                Idna::to_ascii_with("a_b.com", strict).unwrap_err().label,
+                "a_b"
+            );
+            assert_eq!(
+                Idna::to_ascii_with("ab--c.com", strict).unwrap_err().label,
+                "ab--c"
+            );
+            assert_eq!(
+                Idna::to_ascii_with("ab--c.com", default).unwrap(),
+                "ab--c.com"
+            );
+            assert_eq!(
+                Idna::to_ascii_with("a..com", strict).unwrap_err().label,
+                "a..com"
+            );
+            assert_eq!(
+                Idna::to_ascii_with("example.com.", strict).unwrap(),
+                "example.com."
+            );
+            assert!(Idna::to_ascii_with("", default).is_ok());
+            assert!(Idna::to_ascii_with("", strict).is_err());
+
+            let long_label = "a".repeat(64);
+            let err = Idna::to_ascii_with(&alloc::format!("{long_label}.com"), strict).unwrap_err();
+            assert_eq!(err.label, long_label);
+            let long_domain = ["a".repeat(63).as_str(); 4].join(".");
+            assert_eq!(
+                Idna::to_ascii_with(&long_domain, default).unwrap(),
+                long_domain
+            );
+            assert!(Idna::to_ascii_with(&long_domain, strict).is_err());
+        }
+    }
+
+    #[test]
+    fn to_ascii_with_should_match_default_processing() {
+        #[cfg(feature = "alloc")]
+        for input in [
+            "meßagefactory.ca",
+            "xn--meagefactory-m9a.ca",
+            "ＥＸＡＭＰＬＥ。com",
+            "faß.de",
+            "a\u{200d}.com",
+            "xn--zz.com",
+            "\u{05d0}1.com",
+            "\u{05d0}\u{0661}.com",
+            "\u{0301}a.com",
+        ] {
+            assert_eq!(
+                Idna::to_ascii_with(input, IdnaOptions::default()),
+                Idna::try_ascii(input),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn to_ascii_with_should_apply_transitional_processing() {
+        #[cfg(feature = "alloc")]
+        {
+            let transitional = IdnaOptions {
+                transitional: true,
+                ..IdnaOptions::default()
+            };
+            assert_eq!(
+                Idna::to_ascii_with("faß.de", transitional).unwrap(),
+                "fass.de"
+            );
+            assert_eq!(
+                Idna::to_ascii_with("\u{3c2}.gr", transitional).unwrap(),
+                Idna::to_ascii_with("\u{3c3}.gr", transitional).unwrap()
+            );
+            assert_eq!(
+                Idna::to_ascii_with("a\u{200d}b.com", transitional).unwrap(),
+                "ab.com"
+            );
+        }
+    }
+
+    #[test]
+    fn to_ascii_with_should_skip_bidi_rule() {
+        #[cfg(feature = "alloc")]
+        {
+            // An RTL label which ends with an LTR character.
+            let input = "\u{05d0}a.com";
+            assert!(Idna::to_ascii_with(input, IdnaOptions::default()).is_err());
+            let options = IdnaOptions {
+                check_bidi: false,
+                ..IdnaOptions::default()
+            };
+            assert!(Idna::to_ascii_with(input, options).is_ok());
+            // Leading combining marks are still rejected.
+            assert!(Idna::to_ascii_with("\u{0301}a.com", options).is_err());
+            // Labels with a joiner are only checked against `CheckJoiners`, whatever the option.
+            let input = "\u{0628}\u{200c}\u{0628}a.com";
+            assert!(Idna::to_ascii_with(input, options).is_ok());
+            assert!(Idna::to_ascii_with(input, IdnaOptions::default()).is_ok());
+            assert!(Idna::to_ascii_with("\u{05d0}\u{200c}a.com", options).is_err());
+        }
+    }
+
+    #[test]
+    fn to_unicode_with_should_validate() {
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(
+                Idna::to_unicode_with("xn--ls8h.LA", IdnaOptions::default()).unwrap(),
+                "💩.la"
+            );
+            assert_eq!(
+                Idna::to_unicode_with("xn--a.com", IdnaOptions::default())
+                    .unwrap_err()
+                    .label,
+                "xn--a"
+            );
+            assert_eq!(
+                Idna::to_unicode_with("xn---foo-jua.com", IdnaOptions::strict())
+                    .unwrap_err()
+                    .label,
+                "xn---foo-jua"
+            );
+        }
+    }
+
+    #[test]
+    fn map_should_report_disallowed_code_points() {
+        #[cfg(feature = "alloc")]
+        {
+            assert_eq!(Idna::map("").unwrap(), "");
+            assert_eq!(Idna::map("\u{ad}").unwrap(), "");
+            assert_eq!(Idna::map("Faß.DE").unwrap(), "faß.de");
+            assert_eq!(Idna::map("\u{2028}"), None);
+        }
+    }
+
+    #[test]
+    fn stages_should_explain_rejected_domains() {
+        #[cfg(feature = "alloc")]
+        {
+            // The label starts with a combining mark once mapped and normalized.
+            let input = "\u{301}Ab.com";
+            assert!(Idna::try_ascii(input).is_err());
+            let mapped = Idna::map(input).unwrap();
+            let normalized = Idna::normalize(&mapped);
+            assert_eq!(normalized, "\u{301}ab.com");
+            let invalid: alloc::vec::Vec<_> = normalized
+                .split('.')
+                .filter(|label| !Idna::is_label_valid(label))
+                .collect();
+            assert_eq!(invalid, ["\u{301}ab"]);
+        }
+        assert!(Idna::is_label_valid("a\u{94d}\u{200d}"));
+        assert!(!Idna::is_label_valid("a\u{200d}"));
+        assert!(!Idna::is_label_valid("\u{5d0}a"));
+    }
 }
@@ -0,0 +1,149 @@
+use crate::generate::{
+    host, label, path, query_and_fragment, text, Source, HIERARCHICAL_SCHEMES, OPAQUE_SCHEMES,
+    SPECIAL_SCHEMES,
+};
+use crate::{Url, UrlSearchParams};
+use arbitrary::{Arbitrary, Error, Result, Unstructured};
+
+impl Source for Unstructured<'_> {
+    type Error = Error;
+
+    fn index(&mut self, len: usize) -> Result<usize> {
+        self.choose_index(len)
+    }
+
+    fn one_in(&mut self, n: u8) -> Result<bool> {
+        self.ratio(1, n)
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        self.arbitrary()
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        self.arbitrary()
+    }
+
+    fn u128(&mut self) -> Result<u128> {
+        self.arbitrary()
+    }
+}
+
+/// Generates a structurally plausible URL string, which is then parsed.
+///
+/// Generated URLs cover special, opaque and hierarchical schemes, credentials, ports, domains
+/// (including internationalized ones), IPv4 and IPv6 hosts, as well as characters that are
+/// percent-encoded or normalized by the parser.
+///
+/// This implementation is only available if the `arbitrary` Cargo feature is enabled.
+///
+/// ```
+/// use arbitrary::{Arbitrary, Unstructured};
+///
+/// let mut u = Unstructured::new(&[4, 8, 15, 16, 23, 42, 4, 8, 15, 16, 23, 42]);
+/// let url = ada_url::Url::arbitrary(&mut u).expect("Invalid URL");
+/// assert!(ada_url::Url::can_parse(url.href(), None));
+/// ```
+impl<'a> Arbitrary<'a> for Url {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut input = String::new();
+        match u.int_in_range(0..=5)? {
+            0 => {
+                input.push_str(u.pick(OPAQUE_SCHEMES)?);
+                input.push(':');
+                input.push_str(&text(u, 30)?);
+            }
+            1 => {
+                input.push_str("file://");
+                if u.one_in(2)? {
+                    input.push_str(&host(u)?);
+                }
+                input.push_str(&path(u)?);
+            }
+            scheme => {
+                let schemes = if scheme == 2 {
+                    HIERARCHICAL_SCHEMES
+                } else {
+                    SPECIAL_SCHEMES
+                };
+                input.push_str(u.pick(schemes)?);
+                input.push_str("://");
+                if u.one_in(4)? {
+                    input.push_str(&label(u)?);
+                    if u.one_in(2)? {
+                        input.push(':');
+                        input.push_str(&text(u, 8)?.replace(['@', '/', '\\', '?', '#'], ""));
+                    }
+                    input.push('@');
+                }
+                input.push_str(&host(u)?);
+                if u.one_in(3)? {
+                    input.push(':');
+                    input.push_str(&u.u16()?.to_string());
+                }
+                input.push_str(&path(u)?);
+            }
+        }
+        query_and_fragment(u, &mut input)?;
+        Url::parse(input, None).map_err(|_| Error::IncorrectFormat)
+    }
+}
+
+/// Generates search params from arbitrary key-value pairs.
+///
+/// This implementation is only available if the `arbitrary` Cargo feature is enabled.
+impl<'a> Arbitrary<'a> for UrlSearchParams {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let pairs = u.arbitrary_iter::<(&str, &str)>()?;
+        let mut params = UrlSearchParams::parse("").map_err(|_| Error::IncorrectFormat)?;
+        for pair in pairs {
+            let (key, value) = pair?;
+            params.append(key, value);
+        }
+        Ok(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Url, UrlSearchParams};
+    use arbitrary::{Arbitrary, Unstructured};
+
+    /// Deterministic bytes from a linear congruential generator.
+    fn bytes(seed: u64, len: usize) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (state >> 56) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_generate_mostly_valid_urls() {
+        let mut valid = 0;
+        let mut host_types = [false; 5];
+        for seed in 0..500 {
+            let data = bytes(seed, 256);
+            if let Ok(url) = Url::arbitrary(&mut Unstructured::new(&data)) {
+                valid += 1;
+                host_types[url.host_type() as usize] = true;
+                // A serialized URL always parses back to itself.
+                assert_eq!(Url::parse(url.href(), None).unwrap(), url);
+            }
+        }
+        assert!(valid > 400, "only {valid} valid URLs");
+        assert_eq!(host_types[..3], [true; 3]);
+    }
+
+    #[test]
+    fn should_generate_search_params() {
+        let data = bytes(42, 256);
+        let params = UrlSearchParams::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        let reparsed = UrlSearchParams::parse(params.to_string()).unwrap();
+        assert_eq!(reparsed.len(), params.len());
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{ParseUrlError, Url};
+use bson::Bson;
+
+/// Converts a URL into a [`Bson::String`] holding its serialization. `bson` also provides the
+/// conversion from `&Url` through its blanket implementation.
+///
+/// This implementation is only available if the `bson` Cargo feature is enabled.
+///
+/// ```
+/// use bson::Bson;
+///
+/// let url = ada_url::Url::parse("https://example.com", None).expect("Invalid URL");
+/// assert_eq!(Bson::from(&url), Bson::String("https://example.com/".into()));
+/// ```
+impl From<Url> for Bson {
+    fn from(value: Url) -> Self {
+        Bson::String(value.href().to_owned())
+    }
+}
+
+/// Parses a [`Bson::String`] into a URL.
+///
+/// Values of any other BSON type are rejected, and the error holds the original value.
+///
+/// This implementation is only available if the `bson` Cargo feature is enabled.
+///
+/// ```
+/// use ada_url::Url;
+/// use bson::Bson;
+///
+/// let url = Url::try_from(&Bson::String("https://example.com".into())).expect("Invalid URL");
+/// assert_eq!(url.href(), "https://example.com/");
+/// assert!(Url::try_from(&Bson::Int32(1)).is_err());
+/// ```
+impl TryFrom<&Bson> for Url {
+    type Error = ParseUrlError<Bson>;
+
+    fn try_from(value: &Bson) -> Result<Self, Self::Error> {
+        match value {
+            Bson::String(input) => Url::parse(input, None).ok(),
+            _ => None,
+        }
+        .ok_or_else(|| ParseUrlError {
+            input: value.clone(),
+        })
+    }
+}
+
+impl TryFrom<Bson> for Url {
+    type Error = ParseUrlError<Bson>;
+
+    fn try_from(value: Bson) -> Result<Self, Self::Error> {
+        Self::try_from(&value).map_err(|_| ParseUrlError { input: value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Url;
+    use bson::{doc, Bson};
+
+    #[test]
+    fn should_convert_from_and_into_bson() {
+        let url = Url::parse("https://example.com/foo?bar", None).unwrap();
+        let value = Bson::from(&url);
+        assert_eq!(value.as_str(), Some("https://example.com/foo?bar"));
+        assert_eq!(Url::try_from(value).unwrap(), url);
+
+        let err = Url::try_from(Bson::String("invalid".into())).unwrap_err();
+        assert_eq!(err.input, Bson::String("invalid".into()));
+        assert!(Url::try_from(Bson::Null).is_err());
+
+        let document = doc! { "url": &url };
+        assert_eq!(document.get_str("url").unwrap(), url.href());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_round_trip_through_serializer() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Page {
+            url: Url,
+        }
+
+        let page = Page {
+            url: Url::parse("https://example.com/foo?bar", None).unwrap(),
+        };
+        // The raw serializer is the one used by the `mongodb` driver.
+        let bytes = bson::to_vec(&page).unwrap();
+        let raw = bson::RawDocument::from_bytes(&bytes).unwrap();
+        assert_eq!(raw.get_str("url").unwrap(), page.url.href());
+        let decoded: Page = bson::from_slice(&bytes).unwrap();
+        assert_eq!(decoded.url, page.url);
+
+        let document = bson::to_document(&page).unwrap();
+        assert_eq!(document.get_str("url").unwrap(), page.url.href());
+        let decoded: Page = bson::from_document(document).unwrap();
+        assert_eq!(decoded.url, page.url);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::Url;
+use diesel::backend::Backend;
+use diesel::deserialize::{self, FromSql};
+use diesel::serialize::{self, Output, ToSql};
+use diesel::sql_types::Text;
+
+/// Writes the serialized URL, as returned by [`Url::href`], to a text column.
+///
+/// This implementation is only available if the `diesel` Cargo feature is enabled.
+impl<DB> ToSql<Text, DB> for Url
+where
+    DB: Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+        self.href().to_sql(out)
+    }
+}
+
+/// Reads and parses a text column. Values that are not valid URLs fail to deserialize.
+///
+/// This implementation is only available if the `diesel` Cargo feature is enabled.
+impl<DB> FromSql<Text, DB> for Url
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let value = String::from_sql(bytes)?;
+        Ok(Url::parse(value, None)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Url;
+    use diesel::prelude::*;
+
+    diesel::table! {
+        links (id) {
+            id -> Integer,
+            url -> Text,
+        }
+    }
+
+    #[derive(Queryable, Insertable, Debug, PartialEq)]
+    #[diesel(table_name = links)]
+    struct Link {
+        id: i32,
+        url: Url,
+    }
+
+    #[test]
+    fn should_round_trip_through_sqlite() {
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        diesel::sql_query("CREATE TABLE links (id INTEGER PRIMARY KEY, url TEXT NOT NULL)")
+            .execute(&mut conn)
+            .unwrap();
+
+        let link = Link {
+            id: 1,
+            url: Url::parse("https://example.com/foo?bar", None).unwrap(),
+        };
+        diesel::insert_into(links::table)
+            .values(&link)
+            .execute(&mut conn)
+            .unwrap();
+        assert_eq!(links::table.first::<Link>(&mut conn).unwrap(), link);
+
+        let stored: String = links::table.select(links::url).first(&mut conn).unwrap();
+        assert_eq!(stored, "https://example.com/foo?bar");
+
+        diesel::sql_query("INSERT INTO links (id, url) VALUES (2, 'invalid')")
+            .execute(&mut conn)
+            .unwrap();
+        let invalid = links::table
+            .filter(links::id.eq(2))
+            .select(links::url)
+            .first::<Url>(&mut conn);
+        assert!(invalid.is_err());
+    }
+}
@@ -0,0 +1,29 @@
+//! Trait implementations for third-party crates, each behind the Cargo feature of the same name.
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(feature = "bson")]
+mod bson;
+#[cfg(feature = "diesel")]
+mod diesel;
+#[cfg(feature = "http")]
+mod http;
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "quickcheck")]
+mod quickcheck;
+#[cfg(feature = "rkyv")]
+mod rkyv;
+#[cfg(feature = "rusqlite")]
+mod rusqlite;
+#[cfg(feature = "sqlx")]
+mod sqlx;
+#[cfg(feature = "url")]
+mod url;
+#[cfg(feature = "utoipa")]
+mod utoipa;
+
+#[cfg(feature = "rkyv")]
+pub use self::rkyv::{ArchivedUrl, UrlResolver};
+#[cfg(feature = "defmt")]
+mod defmt;
@@ -0,0 +1,59 @@
+use crate::{ParseUrlError, Url};
+use bytes::BytesMut;
+use postgres_types::{accepts, to_sql_checked, FromSql, IsNull, ToSql, Type};
+use std::error::Error;
+
+/// Binds the serialized URL, as returned by [`Url::href`], to a `TEXT` or `VARCHAR` parameter.
+///
+/// This implementation is only available if the `postgres` Cargo feature is enabled.
+impl ToSql for Url {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        self.href().to_sql(ty, out)
+    }
+
+    accepts!(TEXT, VARCHAR);
+
+    to_sql_checked!();
+}
+
+/// Reads and parses a `TEXT` or `VARCHAR` value. Values that are not valid URLs fail with a
+/// [`ParseUrlError`].
+///
+/// This implementation is only available if the `postgres` Cargo feature is enabled.
+impl<'a> FromSql<'a> for Url {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let value = <&str as FromSql>::from_sql(ty, raw)?;
+        Url::parse(value, None).map_err(|err| {
+            ParseUrlError {
+                input: err.input.to_owned(),
+            }
+            .into()
+        })
+    }
+
+    accepts!(TEXT, VARCHAR);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Url;
+    use bytes::BytesMut;
+    use postgres_types::{FromSql, ToSql, Type};
+
+    #[test]
+    fn should_round_trip_as_text() {
+        let url = Url::parse("https://example.com/foo?bar", None).unwrap();
+        let mut buf = BytesMut::new();
+        url.to_sql_checked(&Type::TEXT, &mut buf).unwrap();
+        assert_eq!(&buf[..], b"https://example.com/foo?bar");
+        assert_eq!(Url::from_sql(&Type::VARCHAR, &buf).unwrap(), url);
+
+        assert!(url.to_sql_checked(&Type::INT4, &mut buf).is_err());
+        assert!(!<Url as FromSql>::accepts(&Type::BYTEA));
+        assert!(Url::from_sql(&Type::TEXT, b"invalid").is_err());
+    }
+}
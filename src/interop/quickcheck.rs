@@ -0,0 +1,114 @@
+use crate::generate::{host, path, query_and_fragment, Source, SPECIAL_SCHEMES};
+use crate::Url;
+use quickcheck::{Arbitrary, Gen};
+use std::convert::Infallible;
+
+/// Generated inputs which fail to parse, e.g. a domain ending in a number, are retried up to
+/// this many times before falling back to [`FALLBACK`].
+const ATTEMPTS: usize = 16;
+const FALLBACK: &str = "http://example.com/";
+
+impl Source for Gen {
+    type Error = Infallible;
+
+    fn index(&mut self, len: usize) -> Result<usize, Infallible> {
+        Ok(usize::arbitrary(self) % len)
+    }
+
+    fn one_in(&mut self, n: u8) -> Result<bool, Infallible> {
+        Ok(u8::arbitrary(self) % n == 0)
+    }
+
+    fn u16(&mut self) -> Result<u16, Infallible> {
+        Ok(u16::arbitrary(self))
+    }
+
+    fn u32(&mut self) -> Result<u32, Infallible> {
+        Ok(u32::arbitrary(self))
+    }
+
+    fn u128(&mut self) -> Result<u128, Infallible> {
+        Ok(u128::arbitrary(self))
+    }
+}
+
+fn input(g: &mut Gen) -> Result<String, Infallible> {
+    let mut input = format!("{}://{}", g.pick(SPECIAL_SCHEMES)?, host(g)?);
+    if let Some(port) = Option::<u16>::arbitrary(g) {
+        input.push_str(&format!(":{port}"));
+    }
+    input.push_str(&path(g)?);
+    query_and_fragment(g, &mut input)?;
+    Ok(input)
+}
+
+/// Generates URLs with a special scheme, a domain, IPv4 or IPv6 host, an optional port, a path,
+/// an optional query and an optional fragment. Shrinking removes components one at a time.
+///
+/// This implementation is only available if the `quickcheck` Cargo feature is enabled.
+impl Arbitrary for Url {
+    fn arbitrary(g: &mut Gen) -> Self {
+        (0..ATTEMPTS)
+            .find_map(|_| {
+                let input = input(g).unwrap_or_else(|never| match never {});
+                Url::parse(input, None).ok()
+            })
+            .unwrap_or_else(|| Url::parse(FALLBACK, None).expect("Invalid URL"))
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let mut candidates = Vec::new();
+        if self.has_hash() {
+            let mut url = self.clone();
+            url.set_hash(None);
+            candidates.push(url);
+        }
+        if self.has_search() {
+            let mut url = self.clone();
+            url.set_search(None);
+            candidates.push(url);
+        }
+        if self.has_port() {
+            let mut url = self.clone();
+            url.set_port(None).unwrap();
+            candidates.push(url);
+        }
+        if self.pathname() != "/" {
+            let mut url = self.clone();
+            url.set_pathname(Some("/")).unwrap();
+            candidates.push(url);
+        }
+        Box::new(candidates.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Url;
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+
+    quickcheck! {
+        fn should_round_trip(url: Url) -> bool {
+            Url::parse(url.href(), None).unwrap() == url
+        }
+    }
+
+    #[test]
+    fn should_shrink_to_minimal_url() {
+        let mut url = Url::arbitrary(&mut Gen::new(10));
+        while let Some(smaller) = url.shrink().next() {
+            url = smaller;
+        }
+        assert!(!url.has_hash() && !url.has_search() && !url.has_port());
+        assert_eq!(url.pathname(), "/");
+    }
+
+    #[test]
+    fn should_rarely_fall_back() {
+        let mut g = Gen::new(10);
+        let fallbacks = (0..500)
+            .filter(|_| Url::arbitrary(&mut g).href() == super::FALLBACK)
+            .count();
+        assert!(fallbacks < 5, "{fallbacks} fallbacks");
+    }
+}
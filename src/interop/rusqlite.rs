@@ -0,0 +1,61 @@
+use crate::{ParseUrlError, Url};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+
+/// Stores the serialized URL, as returned by [`Url::href`], as text.
+///
+/// This implementation is only available if the `rusqlite` Cargo feature is enabled.
+impl ToSql for Url {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.href()))
+    }
+}
+
+/// Reads and parses a text value.
+///
+/// Values that are not valid URLs fail with a [`FromSqlError::Other`] wrapping a
+/// [`ParseUrlError`] that holds the stored text.
+///
+/// This implementation is only available if the `rusqlite` Cargo feature is enabled.
+impl FromSql for Url {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let value = value.as_str()?;
+        Url::parse(value, None).map_err(|err| {
+            FromSqlError::Other(Box::new(ParseUrlError {
+                input: err.input.to_owned(),
+            }))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Url;
+    use rusqlite::Connection;
+
+    #[test]
+    fn should_round_trip_through_sqlite() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE links (url TEXT NOT NULL)", ())
+            .unwrap();
+
+        let url = Url::parse("https://example.com/foo?bar", None).unwrap();
+        conn.execute("INSERT INTO links (url) VALUES (?1), ('invalid')", [&url])
+            .unwrap();
+
+        let stored: String = conn
+            .query_row("SELECT url FROM links LIMIT 1", (), |row| row.get(0))
+            .unwrap();
+        assert_eq!(stored, "https://example.com/foo?bar");
+        let decoded: Url = conn
+            .query_row("SELECT url FROM links LIMIT 1", (), |row| row.get(0))
+            .unwrap();
+        assert_eq!(decoded, url);
+
+        let err = conn
+            .query_row("SELECT url FROM links LIMIT 1 OFFSET 1", (), |row| {
+                row.get::<_, Url>(0)
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid url: \"invalid\""));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{ParseUrlError, Url};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::{Database, Decode, Encode, Type};
+
+/// Stores a URL in the same column types as strings, such as `TEXT` or `VARCHAR`.
+///
+/// This implementation is only available if the `sqlx` Cargo feature is enabled.
+impl<DB: Database> Type<DB> for Url
+where
+    String: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <String as Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <String as Type<DB>>::compatible(ty)
+    }
+}
+
+/// Encodes the serialized URL, as returned by [`Url::href`].
+///
+/// This implementation is only available if the `sqlx` Cargo feature is enabled.
+impl<'q, DB: Database> Encode<'q, DB> for Url
+where
+    String: Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        <String as Encode<'q, DB>>::encode(self.href().to_owned(), buf)
+    }
+
+    fn size_hint(&self) -> usize {
+        self.href().len()
+    }
+}
+
+/// Decodes and parses a string column. Values that are not valid URLs fail to decode.
+///
+/// This implementation is only available if the `sqlx` Cargo feature is enabled.
+impl<'r, DB: Database> Decode<'r, DB> for Url
+where
+    &'r str: Decode<'r, DB>,
+{
+    fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        let value = <&'r str as Decode<'r, DB>>::decode(value)?;
+        Url::parse(value, None).map_err(|err| {
+            ParseUrlError {
+                input: err.input.to_owned(),
+            }
+            .into()
+        })
+    }
+}
+
+#[cfg(all(test, not(target_family = "wasm")))]
+mod tests {
+    use crate::Url;
+    use sqlx::sqlite::SqlitePool;
+
+    #[tokio::test]
+    async fn should_round_trip_through_sqlite() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE links (url TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let url = Url::parse("https://example.com/foo?bar", None).unwrap();
+        sqlx::query("INSERT INTO links (url) VALUES (?), ('invalid')")
+            .bind(&url)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let stored: Vec<String> = sqlx::query_scalar("SELECT url FROM links")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(stored, ["https://example.com/foo?bar", "invalid"]);
+
+        let decoded: Url = sqlx::query_scalar("SELECT url FROM links LIMIT 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(decoded, url);
+
+        let invalid = sqlx::query_scalar::<_, Url>("SELECT url FROM links LIMIT 1 OFFSET 1")
+            .fetch_one(&pool)
+            .await;
+        assert!(invalid.is_err());
+    }
+}
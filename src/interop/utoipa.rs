@@ -0,0 +1,60 @@
+use crate::Url;
+use utoipa::openapi::{ObjectBuilder, RefOr, Schema, SchemaFormat, Type};
+
+/// Describes a URL as a string with the `uri` format in OpenAPI documents.
+///
+/// This implementation is only available if the `utoipa` Cargo feature is enabled.
+///
+/// ```
+/// use utoipa::PartialSchema;
+///
+/// let schema = serde_json::to_value(ada_url::Url::schema()).unwrap();
+/// assert_eq!(schema["type"], "string");
+/// assert_eq!(schema["format"], "uri");
+/// ```
+impl utoipa::PartialSchema for Url {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .format(Some(SchemaFormat::Custom("uri".into())))
+            .examples(["https://example.com/"])
+            .into()
+    }
+}
+
+impl utoipa::ToSchema for Url {
+    fn name() -> std::borrow::Cow<'static, str> {
+        "Url".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Url;
+    use utoipa::ToSchema;
+
+    #[derive(ToSchema)]
+    #[allow(dead_code)]
+    struct Link {
+        href: Url,
+        mirror: Option<Url>,
+    }
+
+    #[test]
+    fn should_be_referenced_as_component() {
+        let schema = serde_json::to_value(<Link as utoipa::PartialSchema>::schema()).unwrap();
+        assert_eq!(
+            schema["properties"]["href"]["$ref"],
+            "#/components/schemas/Url"
+        );
+        assert_eq!(schema["required"], serde_json::json!(["href"]));
+
+        let mut schemas = Vec::new();
+        Link::schemas(&mut schemas);
+        let (name, url) = schemas.iter().find(|(name, _)| name == "Url").unwrap();
+        assert_eq!(name, "Url");
+        let url = serde_json::to_value(url).unwrap();
+        assert_eq!(url["type"], "string");
+        assert_eq!(url["format"], "uri");
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{ParseUrlError, Url};
+use alloc::boxed::Box;
+use alloc::string::String;
+
+/// A value which can be converted into a [`Url`], parsing it if needed, so that functions can
+/// accept either parsed URLs or strings.
+///
+/// This trait is only available if the `alloc` Cargo feature is enabled.
+///
+/// ```
+/// use ada_url::{IntoUrl, Url};
+///
+/// fn host_of(url: impl IntoUrl) -> Option<String> {
+///     let url = url.into_url().ok()?;
+///     Some(url.host().to_owned())
+/// }
+///
+/// let parsed = Url::parse("https://example.com/a", None).expect("Invalid URL");
+/// assert_eq!(host_of(&parsed).as_deref(), Some("example.com"));
+/// assert_eq!(host_of(parsed).as_deref(), Some("example.com"));
+/// assert_eq!(host_of("https://example.org").as_deref(), Some("example.org"));
+/// assert_eq!(host_of(String::from("not a url")), None);
+/// ```
+pub trait IntoUrl {
+    /// Converts the value into a [`Url`].
+    fn into_url(self) -> Result<Url, ParseUrlError<Box<str>>>;
+}
+
+impl IntoUrl for Url {
+    fn into_url(self) -> Result<Url, ParseUrlError<Box<str>>> {
+        Ok(self)
+    }
+}
+
+impl IntoUrl for &Url {
+    fn into_url(self) -> Result<Url, ParseUrlError<Box<str>>> {
+        Ok(self.clone())
+    }
+}
+
+impl IntoUrl for &str {
+    fn into_url(self) -> Result<Url, ParseUrlError<Box<str>>> {
+        self.parse()
+    }
+}
+
+impl IntoUrl for String {
+    fn into_url(self) -> Result<Url, ParseUrlError<Box<str>>> {
+        Url::parse(self, None).map_err(|ParseUrlError { input }| ParseUrlError {
+            input: input.into_boxed_str(),
+        })
+    }
+}
+
+impl IntoUrl for &String {
+    fn into_url(self) -> Result<Url, ParseUrlError<Box<str>>> {
+        self.as_str().into_url()
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{Url, UrlSearchParams};
+
+/// A view of the parameters of a [`magnet:`](https://en.wikipedia.org/wiki/Magnet_URI_scheme)
+/// URI, with their values percent-decoded.
+///
+/// Multi-valued parameters may be repeated, as in `tr=a&tr=b`, or numbered, as in
+/// `tr.1=a&tr.2=b`, and both forms are returned in order of appearance.
+///
+/// ```
+/// use ada_url::{MagnetUrl, Url};
+///
+/// let url = Url::parse(
+///     "magnet:?xt=urn:btih:c12fe1c06bba254a9dc9f519b335aa7c1367a88a&dn=Some+File%21\
+///      &tr=udp%3A%2F%2Ftracker.example%3A1337&tr.2=https%3A%2F%2Ft.example%2Fannounce",
+///     None,
+/// )
+/// .expect("Invalid URL");
+/// let magnet = MagnetUrl::from_url(&url).unwrap();
+/// assert_eq!(
+///     magnet.info_hashes().collect::<Vec<_>>(),
+///     ["c12fe1c06bba254a9dc9f519b335aa7c1367a88a"]
+/// );
+/// assert_eq!(magnet.display_name(), Some("Some File!"));
+/// assert_eq!(
+///     magnet.trackers().collect::<Vec<_>>(),
+///     ["udp://tracker.example:1337", "https://t.example/announce"]
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct MagnetUrl {
+    params: UrlSearchParams,
+}
+
+impl MagnetUrl {
+    /// Returns the view of a `magnet:` URL, or `None` for other schemes.
+    #[must_use]
+    pub fn from_url(url: &Url) -> Option<Self> {
+        (url.protocol() == "magnet:").then(|| Self {
+            params: UrlSearchParams::from_url(url),
+        })
+    }
+
+    /// Returns the exact topics (`xt`), e.g. `urn:btih:<info hash>`.
+    pub fn exact_topics(&self) -> impl DoubleEndedIterator<Item = &str> {
+        self.values("xt")
+    }
+
+    /// Returns the BitTorrent info hashes of the exact topics, i.e. the v1 `urn:btih:` hashes
+    /// and the v2 `urn:btmh:` multihashes, without their prefix.
+    pub fn info_hashes(&self) -> impl DoubleEndedIterator<Item = &str> {
+        self.exact_topics().filter_map(|topic| {
+            topic
+                .strip_prefix("urn:btih:")
+                .or_else(|| topic.strip_prefix("urn:btmh:"))
+        })
+    }
+
+    /// Returns the first display name (`dn`), the file name to show before metadata is known.
+    #[must_use]
+    pub fn display_name(&self) -> Option<&str> {
+        self.values("dn").next()
+    }
+
+    /// Returns the tracker URLs (`tr`).
+    pub fn trackers(&self) -> impl DoubleEndedIterator<Item = &str> {
+        self.values("tr")
+    }
+
+    /// Returns the web seed URLs (`ws`).
+    pub fn web_seeds(&self) -> impl DoubleEndedIterator<Item = &str> {
+        self.values("ws")
+    }
+
+    /// Returns the first exact length (`xl`), in bytes, if it is a valid number.
+    #[must_use]
+    pub fn exact_length(&self) -> Option<u64> {
+        self.values("xl").next()?.parse().ok()
+    }
+
+    /// Returns the values of a parameter, including its numbered forms, e.g. `tr.1`.
+    ///
+    /// ```
+    /// use ada_url::{MagnetUrl, Url};
+    ///
+    /// let url = Url::parse("magnet:?kt=rust&kt.1=url&kt.x=no", None).expect("Invalid URL");
+    /// let magnet = MagnetUrl::from_url(&url).unwrap();
+    /// assert_eq!(magnet.values("kt").collect::<Vec<_>>(), ["rust", "url"]);
+    /// ```
+    pub fn values<'a>(&'a self, name: &'a str) -> impl DoubleEndedIterator<Item = &'a str> {
+        self.params.entries().filter_map(move |(key, value)| {
+            let index = key.strip_prefix(name)?;
+            let is_numbered = index
+                .strip_prefix('.')
+                .is_some_and(|n| !n.is_empty() && n.bytes().all(|byte| byte.is_ascii_digit()));
+            (index.is_empty() || is_numbered).then_some(value)
+        })
+    }
+
+    /// Returns all the parameters, for those without a dedicated method.
+    #[must_use]
+    pub fn params(&self) -> &UrlSearchParams {
+        &self.params
+    }
+}
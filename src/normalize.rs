@@ -0,0 +1,136 @@
+use crate::{ComponentFlags, Url, UrlSearchParams};
+
+/// Normalizations applied by [`Url::normalized`] on top of the canonicalization of the parser,
+/// to treat URLs which usually identify the same resource as equal.
+///
+/// The default options apply no normalization.
+///
+/// ```
+/// use ada_url::{NormalizeOptions, Url};
+///
+/// let options = NormalizeOptions {
+///     remove_fragment: true,
+///     sort_query: true,
+///     ..NormalizeOptions::default()
+/// };
+/// let url = Url::parse("https://example.com/?b=2&a=1#top", None).expect("Invalid URL");
+/// assert_eq!(url.normalized(options).href(), "https://example.com/?a=1&b=2");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NormalizeOptions {
+    /// Remove the fragment, including an empty one.
+    pub remove_fragment: bool,
+    /// Sort the query parameters by name, keeping the order of parameters with the same name,
+    /// and serialize them as `application/x-www-form-urlencoded`.
+    pub sort_query: bool,
+    /// Remove an empty query, i.e. a trailing `?`.
+    pub remove_empty_query: bool,
+    /// Remove the trailing slash of a path other than `/`, e.g. `/a/` becomes `/a`.
+    pub remove_trailing_slash: bool,
+}
+
+impl NormalizeOptions {
+    /// Options applying every normalization, e.g. to deduplicate the URLs of a crawl frontier.
+    #[must_use]
+    pub const fn all() -> Self {
+        Self {
+            remove_fragment: true,
+            sort_query: true,
+            remove_empty_query: true,
+            remove_trailing_slash: true,
+        }
+    }
+}
+
+impl Url {
+    /// Returns a copy of this URL with the given normalizations applied.
+    ///
+    /// The parser already lowercases the scheme and host of special URLs, removes their default
+    /// port, resolves `.` and `..` segments and percent-encodes the characters which need it.
+    ///
+    /// ```
+    /// use ada_url::{NormalizeOptions, Url};
+    ///
+    /// let url = Url::parse("HTTPS://Example.com:443/a/./b/?#", None).expect("Invalid URL");
+    /// assert_eq!(url.href(), "https://example.com/a/b/?#");
+    /// assert_eq!(
+    ///     url.normalized(NormalizeOptions::all()).href(),
+    ///     "https://example.com/a/b"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn normalized(&self, options: NormalizeOptions) -> Url {
+        let mut url = self.clone();
+        if options.remove_fragment && self.has_hash() {
+            url.set_hash(None);
+        }
+        if options.sort_query && !self.search().is_empty() {
+            let mut params = UrlSearchParams::from_url(self);
+            params.sort();
+            url.set_search_params(&params);
+        }
+        if options.remove_empty_query && self.has_search() && self.search().is_empty() {
+            url.set_search(None);
+        }
+        if options.remove_trailing_slash && !self.has_opaque_path() {
+            let pathname = self.pathname();
+            if let Some(trimmed) = pathname.strip_suffix('/').filter(|p| !p.is_empty()) {
+                let _ = url.set_pathname(Some(trimmed));
+            }
+        }
+        url
+    }
+
+    /// Returns true if this URL and `other` are equal once both are normalized with the given
+    /// options.
+    ///
+    /// ```
+    /// use ada_url::{NormalizeOptions, Url};
+    ///
+    /// let url = Url::parse("https://example.com/a?b=1&c=2", None).expect("Invalid URL");
+    /// let other = Url::parse("https://example.com/a?c=2&b=1#d", None).expect("Invalid URL");
+    /// assert_ne!(url, other);
+    /// assert!(url.normalized_eq(&other, NormalizeOptions::all()));
+    /// ```
+    #[must_use]
+    pub fn normalized_eq(&self, other: &Url, options: NormalizeOptions) -> bool {
+        self == other || self.normalized(options) == other.normalized(options)
+    }
+
+    /// Returns true if this URL and `other` are equal, ignoring a trailing slash in their paths.
+    ///
+    /// ```
+    /// use ada_url::Url;
+    ///
+    /// let url = Url::parse("https://example.com/a/?b", None).expect("Invalid URL");
+    /// assert!(url.eq_ignoring_trailing_slash(&Url::parse("https://example.com/a?b", None).unwrap()));
+    /// assert!(!url.eq_ignoring_trailing_slash(&Url::parse("https://example.com/a/b", None).unwrap()));
+    /// ```
+    #[must_use]
+    pub fn eq_ignoring_trailing_slash(&self, other: &Url) -> bool {
+        let options = NormalizeOptions {
+            remove_trailing_slash: true,
+            ..NormalizeOptions::default()
+        };
+        self.normalized_eq(other, options)
+    }
+
+    /// Returns true if this URL and `other` are equal, comparing their ports with
+    /// [`Url::port_or_known_default`], so that an explicit default port equals no port.
+    ///
+    /// The parser already removes the default port of special schemes, e.g. `:443` of `https:`,
+    /// so this only makes the intent explicit, e.g. when comparing URLs from several sources.
+    ///
+    /// ```
+    /// use ada_url::Url;
+    ///
+    /// let url = Url::parse("wss://example.com:443/", None).expect("Invalid URL");
+    /// assert!(url.eq_ignoring_default_port(&Url::parse("wss://example.com/", None).unwrap()));
+    /// assert!(!url.eq_ignoring_default_port(&Url::parse("wss://example.com:80/", None).unwrap()));
+    /// ```
+    #[must_use]
+    pub fn eq_ignoring_default_port(&self, other: &Url) -> bool {
+        (self.diff(other) & !ComponentFlags::PORT).is_empty()
+            && self.port_or_known_default() == other.port_or_known_default()
+    }
+}
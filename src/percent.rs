@@ -0,0 +1,286 @@
+//! Percent-encoding and decoding with the encode sets of the
+//! [WHATWG URL Standard](https://url.spec.whatwg.org/#percent-encoded-bytes), using the tables
+//! Ada parses URLs with.
+//!
+//! This module is only available if the `alloc` Cargo feature is enabled.
+//!
+//! ```
+//! use ada_url::percent::{self, EncodeSet};
+//!
+//! let encoded = percent::encode("a b/c?d", EncodeSet::Component);
+//! assert_eq!(encoded, "a%20b%2Fc%3Fd");
+//! assert_eq!(percent::decode(&encoded), b"a b/c?d");
+//! ```
+
+use crate::ffi;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A percent-encode set, i.e. the code points which are percent-encoded in addition to
+/// non-ASCII code points.
+///
+/// Each set is a superset of the previous one, except [`EncodeSet::SpecialQuery`] which is a
+/// superset of [`EncodeSet::Query`] only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum EncodeSet {
+    /// The [C0 control percent-encode set](https://url.spec.whatwg.org/#c0-control-percent-encode-set):
+    /// C0 controls and code points greater than U+007E (~).
+    C0Control = 0,
+    /// The [fragment percent-encode set](https://url.spec.whatwg.org/#fragment-percent-encode-set),
+    /// adding space, `"`, `<`, `>` and `` ` ``.
+    Fragment = 1,
+    /// The [query percent-encode set](https://url.spec.whatwg.org/#query-percent-encode-set),
+    /// adding space, `"`, `#`, `<` and `>` to the C0 control set.
+    Query = 2,
+    /// The [special-query percent-encode set](https://url.spec.whatwg.org/#special-query-percent-encode-set),
+    /// used by special schemes, adding `'`.
+    SpecialQuery = 3,
+    /// The [path percent-encode set](https://url.spec.whatwg.org/#path-percent-encode-set),
+    /// adding `?`, `` ` ``, `{` and `}` to the query set.
+    Path = 4,
+    /// The [userinfo percent-encode set](https://url.spec.whatwg.org/#userinfo-percent-encode-set),
+    /// adding `/`, `:`, `;`, `=`, `@`, `[` to `^` and `|`.
+    Userinfo = 5,
+    /// The [component percent-encode set](https://url.spec.whatwg.org/#component-percent-encode-set),
+    /// adding `$` to `&`, `+` and `,`, as used by JavaScript's `encodeURIComponent`.
+    Component = 6,
+    /// The [`application/x-www-form-urlencoded` percent-encode set](https://url.spec.whatwg.org/#application-x-www-form-urlencoded-percent-encode-set),
+    /// adding `!`, `'` to `)` and `~`. Spaces are percent-encoded rather than replaced with `+`.
+    FormUrlencoded = 7,
+}
+
+/// Percent-encodes the UTF-8 bytes of `input` which belong to `set`, and all non-ASCII bytes.
+///
+/// ```
+/// use ada_url::percent::{self, EncodeSet};
+/// assert_eq!(percent::encode("/a b?#", EncodeSet::Path), "/a%20b%3F%23");
+/// assert_eq!(percent::encode("café", EncodeSet::C0Control), "caf%C3%A9");
+/// ```
+#[must_use]
+pub fn encode(input: &str, set: EncodeSet) -> String {
+    String::from(
+        unsafe { ffi::ada_percent_encode(input.as_ptr().cast(), input.len(), set as u8) }.as_ref(),
+    )
+}
+
+/// Percent-decodes `input`. Percent signs which are not followed by two hexadecimal digits are
+/// kept as is. The result may not be valid UTF-8.
+///
+/// ```
+/// use ada_url::percent;
+/// assert_eq!(percent::decode("caf%C3%A9%2"), "café%2".as_bytes());
+/// assert_eq!(percent::decode("%FF"), [0xff]);
+/// ```
+#[must_use]
+pub fn decode(input: &str) -> Vec<u8> {
+    unsafe { ffi::ada_percent_decode(input.as_ptr().cast(), input.len()) }
+        .as_bytes()
+        .to_vec()
+}
+
+/// The characters JavaScript's `encodeURI` keeps as is, besides ASCII alphanumerics.
+const URI_UNESCAPED: &str = "-_.!~*'();/?:@&=+$,#";
+
+/// The characters JavaScript's `decodeURI` keeps percent-encoded.
+const URI_RESERVED: &str = ";/?:@&=+$,#";
+
+/// Error type of [`decode_uri_component`] and [`decode_uri`], the equivalent of JavaScript's
+/// `URIError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+#[display("URI malformed at byte {index}")]
+pub struct UriError {
+    /// The byte offset of the `%` starting the malformed escape sequence.
+    pub index: usize,
+}
+
+/// Percent-encodes `input` like JavaScript's `encodeURIComponent`, keeping only ASCII
+/// alphanumerics and `-_.!~*'()` as is.
+///
+/// ```
+/// use ada_url::percent;
+/// assert_eq!(percent::encode_uri_component("a b&c=d/é"), "a%20b%26c%3Dd%2F%C3%A9");
+/// assert_eq!(percent::encode_uri_component("-_.!~*'()"), "-_.!~*'()");
+/// ```
+#[must_use]
+pub fn encode_uri_component(input: &str) -> String {
+    encode(input, EncodeSet::Component)
+}
+
+/// Percent-encodes `input` like JavaScript's `encodeURI`, which also keeps the characters with a
+/// meaning in URIs, `;/?:@&=+$,#`, as is.
+///
+/// ```
+/// use ada_url::percent;
+/// assert_eq!(
+///     percent::encode_uri("https://example.com/a b?q=é#[x]"),
+///     "https://example.com/a%20b?q=%C3%A9#%5Bx%5D"
+/// );
+/// ```
+#[must_use]
+pub fn encode_uri(input: &str) -> String {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    let mut output = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        if byte.is_ascii_alphanumeric() || URI_UNESCAPED.as_bytes().contains(&byte) {
+            output.push(char::from(byte));
+        } else {
+            output.push('%');
+            output.push(char::from(HEX[usize::from(byte >> 4)]));
+            output.push(char::from(HEX[usize::from(byte & 0xf)]));
+        }
+    }
+    output
+}
+
+/// Percent-decodes `input` like JavaScript's `decodeURIComponent`.
+///
+/// Unlike [`decode`], fails if a `%` is not followed by two hexadecimal digits, or if the
+/// escape sequences do not encode valid UTF-8.
+///
+/// ```
+/// use ada_url::percent;
+/// assert_eq!(percent::decode_uri_component("a%20b%26c%3Dd%2F%C3%A9").unwrap(), "a b&c=d/é");
+/// assert_eq!(percent::decode_uri_component("100%").unwrap_err().index, 3);
+/// ```
+pub fn decode_uri_component(input: &str) -> Result<String, UriError> {
+    decode_uri_except(input, "")
+}
+
+/// Percent-decodes `input` like JavaScript's `decodeURI`, which keeps the escape sequences of
+/// `;/?:@&=+$,#` encoded.
+///
+/// ```
+/// use ada_url::percent;
+/// assert_eq!(percent::decode_uri("/a%20b%2Fc%3f%C3%A9").unwrap(), "/a b%2Fc%3fé");
+/// assert!(percent::decode_uri("%C3%28").is_err());
+/// ```
+pub fn decode_uri(input: &str) -> Result<String, UriError> {
+    decode_uri_except(input, URI_RESERVED)
+}
+
+fn decode_uri_except(input: &str, reserved: &str) -> Result<String, UriError> {
+    let mut output = String::with_capacity(input.len());
+    let mut written = 0;
+    let mut i = 0;
+    while let Some(offset) = input[i..].find('%') {
+        let start = i + offset;
+        let (c, len) = crate::pretty::decode_char(&input.as_bytes()[start..])
+            .ok_or(UriError { index: start })?;
+        i = start + len;
+        if !reserved.contains(c) {
+            output.push_str(&input[written..start]);
+            output.push(c);
+            written = i;
+        }
+    }
+    output.push_str(&input[written..]);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::percent::{
+        decode, decode_uri, decode_uri_component, encode, encode_uri, encode_uri_component,
+        EncodeSet, UriError,
+    };
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    const SETS: [EncodeSet; 8] = [
+        EncodeSet::C0Control,
+        EncodeSet::Fragment,
+        EncodeSet::Query,
+        EncodeSet::SpecialQuery,
+        EncodeSet::Path,
+        EncodeSet::Userinfo,
+        EncodeSet::Component,
+        EncodeSet::FormUrlencoded,
+    ];
+
+    #[test]
+    fn should_encode_ascii_per_set() {
+        let kept: Vec<String> = SETS
+            .iter()
+            .map(|set| {
+                (' '..='~')
+                    .filter(|c| encode(c.encode_utf8(&mut [0; 4]), *set).len() == 1)
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            kept,
+            [
+                " !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~",
+                "!#$%&'()*+,-./0123456789:;=?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_abcdefghijklmnopqrstuvwxyz{|}~",
+                "!$%&'()*+,-./0123456789:;=?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~",
+                "!$%&()*+,-./0123456789:;=?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~",
+                "!$%&'()*+,-./0123456789:;=@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_abcdefghijklmnopqrstuvwxyz|~",
+                "!$%&'()*+,-.0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ_abcdefghijklmnopqrstuvwxyz~",
+                "!'()*-.0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ_abcdefghijklmnopqrstuvwxyz~",
+                "*-.0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ_abcdefghijklmnopqrstuvwxyz",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_encode_non_ascii() {
+        for set in SETS {
+            assert_eq!(encode("日本", set), "%E6%97%A5%E6%9C%AC");
+        }
+        assert_eq!(encode("", EncodeSet::Component), "");
+    }
+
+    #[test]
+    fn should_decode() {
+        assert_eq!(decode(""), b"");
+        assert_eq!(decode("no-escapes"), b"no-escapes");
+        assert_eq!(decode("%e6%97%a5%E6%9C%AC"), "日本".as_bytes());
+        assert_eq!(decode("%%41%4"), b"%A%4");
+        assert_eq!(decode("%zz+"), b"%zz+");
+        for set in SETS {
+            let input = "a b/c?d#e%f=g&h+i日本";
+            assert_eq!(decode(&encode(input, set)), input.as_bytes());
+        }
+    }
+
+    #[test]
+    fn should_encode_uri_like_javascript() {
+        let ascii: String = (' '..='~').collect();
+        assert_eq!(
+            encode_uri_component(&ascii),
+            "%20!%22%23%24%25%26'()*%2B%2C-.%2F0123456789%3A%3B%3C%3D%3E%3F%40ABCDEFGHIJKLMNOPQRSTUVWXYZ%5B%5C%5D%5E_%60abcdefghijklmnopqrstuvwxyz%7B%7C%7D~"
+        );
+        assert_eq!(
+            encode_uri(&ascii),
+            "%20!%22#$%25&'()*+,-./0123456789:;%3C=%3E?@ABCDEFGHIJKLMNOPQRSTUVWXYZ%5B%5C%5D%5E_%60abcdefghijklmnopqrstuvwxyz%7B%7C%7D~"
+        );
+        assert_eq!(encode_uri("\u{7f}\u{0}😀"), "%7F%00%F0%9F%98%80");
+    }
+
+    #[test]
+    fn should_decode_uri_like_javascript() {
+        assert_eq!(decode_uri_component("").unwrap(), "");
+        assert_eq!(decode_uri_component("%F0%9F%98%80+%2b").unwrap(), "😀++");
+        assert_eq!(decode_uri("%23%2b%2F%20%25").unwrap(), "%23%2b%2F %");
+        assert_eq!(decode_uri_component("%23%2b%2F%20%25").unwrap(), "#+/ %");
+        for (input, index) in [
+            ("%", 0),
+            ("a%2", 1),
+            ("ab%zz", 2),
+            ("%C3", 0),
+            ("%C3%28", 0),
+            ("%C0%80", 0),
+            ("%ED%A0%80", 0),
+            ("%FF", 0),
+            ("%41%E6%97%A5%E6%9C", 12),
+        ] {
+            assert_eq!(
+                decode_uri_component(input),
+                Err(UriError { index }),
+                "{input}"
+            );
+            assert_eq!(decode_uri(input), Err(UriError { index }), "{input}");
+        }
+    }
+}
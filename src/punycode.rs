@@ -0,0 +1,100 @@
+//! Punycode encoding and decoding of single domain labels, as defined by
+//! [RFC 3492](https://www.rfc-editor.org/rfc/rfc3492).
+//!
+//! Unlike [`Idna`](crate::Idna), labels are neither mapped nor validated, so labels rejected by
+//! UTS #46 processing can still be converted. The `xn--` prefix is neither added nor expected.
+//!
+//! This module is only available if the `alloc` Cargo feature is enabled.
+//!
+//! ```
+//! use ada_url::punycode;
+//! assert_eq!(punycode::encode_label("bücher").unwrap(), "bcher-kva");
+//! assert_eq!(punycode::decode_label("bcher-kva").unwrap(), "bücher");
+//! ```
+
+use crate::{ffi, IdnaError};
+use alloc::string::String;
+
+/// Encodes a single label with Punycode, without the `xn--` prefix.
+///
+/// ```
+/// use ada_url::punycode;
+/// // Labels are not mapped, so upper case characters are kept.
+/// assert_eq!(punycode::encode_label("Bücher").unwrap(), "Bcher-kva");
+/// ```
+pub fn encode_label(label: &str) -> Result<String, IdnaError> {
+    let mut out = ffi::ada_owned_string {
+        data: core::ptr::null(),
+        length: 0,
+    };
+    if unsafe { ffi::ada_idna_punycode_encode(label.as_ptr().cast(), label.len(), &mut out) } {
+        Ok(String::from(out.as_ref()))
+    } else {
+        Err(IdnaError {
+            label: label.into(),
+        })
+    }
+}
+
+/// Decodes a single Punycode label, without the `xn--` prefix. Returns an error if the label is
+/// not valid Punycode.
+///
+/// ```
+/// use ada_url::punycode;
+/// assert_eq!(punycode::decode_label("ls8h").unwrap(), "💩");
+/// assert!(punycode::decode_label("zz").is_err());
+/// ```
+pub fn decode_label(label: &str) -> Result<String, IdnaError> {
+    let mut out = ffi::ada_owned_string {
+        data: core::ptr::null(),
+        length: 0,
+    };
+    if unsafe { ffi::ada_idna_punycode_decode(label.as_ptr().cast(), label.len(), &mut out) } {
+        Ok(String::from(out.as_ref()))
+    } else {
+        Err(IdnaError {
+            label: label.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::punycode::{decode_label, encode_label};
+
+    #[test]
+    fn should_round_trip_rfc_3492_samples() {
+        for (label, encoded) in [
+            ("ليهمابتكلموشعربي؟", "egbpdaj6bu4bxfgehfvwxn"),
+            ("他们为什么不说中文", "ihqwcrb4cv8a8dqg056pqjye"),
+            (
+                "почемужеонинеговорятпорусски",
+                "b1abfaaepdrnnbgefbadotcwatmq2g4l",
+            ),
+            ("3年B組金八先生", "3B-ww4c5e180e575a65lsy2b"),
+            ("-> $1.00 <-", "-> $1.00 <--"),
+        ] {
+            assert_eq!(encode_label(label).unwrap(), encoded);
+            assert_eq!(decode_label(encoded).unwrap(), label);
+        }
+    }
+
+    #[test]
+    fn should_handle_labels_rejected_by_mapping() {
+        // Both labels are rejected by UTS #46: the first contains a disallowed character, the
+        // second starts with a combining mark.
+        for label in ["a_\u{2028}b", "\u{0301}a"] {
+            let encoded = encode_label(label).unwrap();
+            assert_eq!(decode_label(&encoded).unwrap(), label);
+        }
+    }
+
+    #[test]
+    fn should_reject_invalid_punycode() {
+        assert_eq!(decode_label("zz").unwrap_err().label, "zz");
+        assert!(decode_label("a!").is_err());
+        assert!(decode_label("é-").is_err());
+        // Decodes to a code point larger than U+10FFFF.
+        assert!(decode_label("99999999").is_err());
+    }
+}
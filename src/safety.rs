@@ -0,0 +1,143 @@
+use crate::{HostType, Url};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Hosts with more labels than this are reported as [`SafetyFinding::ExcessiveSubdomains`].
+const MAX_HOST_LABELS: usize = 5;
+
+/// A suspicious property of a URL, as reported by [`Url::safety_report`].
+///
+/// Findings are heuristics to flag URLs for review, e.g. by a link scanner, rather than proof
+/// that a URL is malicious.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display)]
+#[non_exhaustive]
+pub enum SafetyFinding {
+    /// The URL has a username or password, which can disguise the actual host, as in
+    /// `http://bank.com@evil.example/`. `looks_like_host` is true if the username contains a
+    /// dot, as a domain would.
+    #[display("URL has credentials before its host")]
+    Userinfo { looks_like_host: bool },
+    /// The host has more than five labels, as in `bank.com.login.secure.evil.example`, which
+    /// can push the registrable domain out of view.
+    #[display("Host has {labels} labels")]
+    ExcessiveSubdomains { labels: usize },
+    /// The host is an IPv4 or IPv6 address instead of a domain.
+    #[display("Host is an IP address")]
+    IpAddressHost,
+    /// The host has Punycode labels, i.e. it is an internationalized domain.
+    #[display("Host has Punycode labels")]
+    PunycodeHost,
+    /// A label of the host is a homograph risk once converted to Unicode.
+    ///
+    /// This variant is only available if the `spoof` Cargo feature is enabled.
+    #[cfg(feature = "spoof")]
+    #[display("Host is a homograph risk: {_0:?}")]
+    HomographRisk(crate::spoof::SpoofRisk),
+    /// The scheme, e.g. `javascript` or `data`, runs code or embeds content instead of
+    /// pointing to a resource.
+    #[display("Scheme {scheme:?} runs code or embeds content")]
+    ActiveContentScheme { scheme: String },
+}
+
+impl Url {
+    /// Returns the suspicious properties of this URL, e.g. for a link scanner, or an empty list
+    /// if there are none.
+    ///
+    /// Homograph risks of internationalized domains are only detected if the `spoof` Cargo
+    /// feature is enabled.
+    ///
+    /// This method is only available if the `alloc` Cargo feature is enabled.
+    ///
+    /// ```
+    /// use ada_url::{SafetyFinding, Url};
+    ///
+    /// let url = Url::parse("http://bank.com@192.168.0.1/login", None).expect("Invalid URL");
+    /// assert_eq!(
+    ///     url.safety_report(),
+    ///     [
+    ///         SafetyFinding::Userinfo { looks_like_host: true },
+    ///         SafetyFinding::IpAddressHost,
+    ///     ]
+    /// );
+    ///
+    /// let url = Url::parse("javascript:alert(1)", None).expect("Invalid URL");
+    /// assert_eq!(
+    ///     url.safety_report(),
+    ///     [SafetyFinding::ActiveContentScheme { scheme: "javascript".into() }]
+    /// );
+    ///
+    /// let url = Url::parse("https://example.com/", None).expect("Invalid URL");
+    /// assert!(url.safety_report().is_empty());
+    /// ```
+    #[must_use]
+    pub fn safety_report(&self) -> Vec<SafetyFinding> {
+        let mut findings = Vec::new();
+        let scheme = self.protocol().trim_end_matches(':');
+        if matches!(scheme, "javascript" | "data" | "vbscript") {
+            findings.push(SafetyFinding::ActiveContentScheme {
+                scheme: scheme.into(),
+            });
+        }
+        if self.has_credentials() {
+            findings.push(SafetyFinding::Userinfo {
+                looks_like_host: self.username().contains('.'),
+            });
+        }
+        match self.host_type() {
+            HostType::IPV4 | HostType::IPV6 => findings.push(SafetyFinding::IpAddressHost),
+            HostType::Domain => {
+                let hostname = self.hostname();
+                let labels = hostname.trim_end_matches('.').split('.').count();
+                if labels > MAX_HOST_LABELS {
+                    findings.push(SafetyFinding::ExcessiveSubdomains { labels });
+                }
+                if hostname.split('.').any(|label| label.starts_with("xn--")) {
+                    findings.push(SafetyFinding::PunycodeHost);
+                    #[cfg(feature = "spoof")]
+                    if let Ok(risks) = crate::spoof::check_host(hostname) {
+                        findings.extend(risks.into_iter().map(SafetyFinding::HomographRisk));
+                    }
+                }
+            }
+            _ => {}
+        }
+        findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SafetyFinding, Url};
+
+    #[test]
+    fn should_report_suspicious_hosts() {
+        let url = Url::parse("https://a.b.c.d.example.com/", None).unwrap();
+        assert_eq!(
+            url.safety_report(),
+            [SafetyFinding::ExcessiveSubdomains { labels: 6 }]
+        );
+        let url = Url::parse("https://a.b.c.example.com./", None).unwrap();
+        assert!(url.safety_report().is_empty());
+        let url = Url::parse("https://user@[::1]/", None).unwrap();
+        assert_eq!(
+            url.safety_report(),
+            [
+                SafetyFinding::Userinfo {
+                    looks_like_host: false
+                },
+                SafetyFinding::IpAddressHost,
+            ]
+        );
+        let url = Url::parse("https://bücher.de/", None).unwrap();
+        assert_eq!(url.safety_report(), [SafetyFinding::PunycodeHost]);
+    }
+
+    #[test]
+    #[cfg(feature = "spoof")]
+    fn should_report_homograph_risks() {
+        let url = Url::parse("https://ѕсоре.com/", None).unwrap();
+        let report = url.safety_report();
+        assert_eq!(report[0], SafetyFinding::PunycodeHost);
+        assert!(matches!(report[1], SafetyFinding::HomographRisk(_)));
+    }
+}
@@ -0,0 +1,601 @@
+//! Conversions between query strings and typed values, in the manner of `serde_urlencoded`.
+
+use crate::{Url, UrlSearchParams};
+use serde::de::value::{Error, MapDeserializer};
+use serde::de::{self, DeserializeOwned, Error as _, IntoDeserializer, Unexpected, Visitor};
+use serde::ser::{self, Impossible, Serialize};
+
+impl Url {
+    /// Deserializes the query of this URL into `T`, parsing it as
+    /// `application/x-www-form-urlencoded` like [`UrlSearchParams::deserialize_into`].
+    ///
+    /// This method is only available if the `serde` Cargo feature is enabled.
+    ///
+    /// ```
+    /// use ada_url::Url;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Search {
+    ///     q: String,
+    ///     page: Option<u32>,
+    /// }
+    ///
+    /// let url = Url::parse("https://example.com/search?q=caf%C3%A9+au+lait&page=2", None)
+    ///     .expect("This is a valid URL. Should have parsed it.");
+    /// let search: Search = url.query_as().unwrap();
+    /// assert_eq!(search.q, "café au lait");
+    /// assert_eq!(search.page, Some(2));
+    /// ```
+    pub fn query_as<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        UrlSearchParams::parse(self.search())
+            .map_err(|_| Error::custom("invalid query"))?
+            .deserialize_into()
+    }
+
+    /// Replaces the query of this URL with the pairs serialized from `value`, like
+    /// [`UrlSearchParams::from_serialize`]. The query is removed if `value` serializes to no
+    /// pairs.
+    ///
+    /// This method is only available if the `serde` Cargo feature is enabled.
+    ///
+    /// ```
+    /// use ada_url::Url;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Search<'a> {
+    ///     q: &'a str,
+    ///     page: Option<u32>,
+    /// }
+    ///
+    /// let mut url = Url::parse("https://example.com/search?old", None)
+    ///     .expect("This is a valid URL. Should have parsed it.");
+    /// url.set_query_from(&Search { q: "café au lait", page: None }).unwrap();
+    /// assert_eq!(url.href(), "https://example.com/search?q=caf%C3%A9+au+lait");
+    /// ```
+    pub fn set_query_from<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let params = UrlSearchParams::from_serialize(value)?;
+        self.set_search_params(&params);
+        Ok(())
+    }
+}
+
+impl UrlSearchParams {
+    /// Deserializes the entries of this `UrlSearchParams` into `T`, which is usually a struct
+    /// with one field per name.
+    ///
+    /// Values are parsed from strings as needed by `T`: integers, floats, booleans, characters,
+    /// unit enum variants and `Option`s, which are `Some` when the name is present. As with
+    /// `serde_urlencoded`, a name repeated for a struct field is an error, and sequences are not
+    /// supported.
+    ///
+    /// This method is only available if the `serde` Cargo feature is enabled.
+    ///
+    /// ```
+    /// use ada_url::UrlSearchParams;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, Debug, PartialEq)]
+    /// #[serde(rename_all = "lowercase")]
+    /// enum Order {
+    ///     Asc,
+    ///     Desc,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Listing {
+    ///     limit: usize,
+    ///     order: Order,
+    ///     #[serde(default)]
+    ///     archived: bool,
+    /// }
+    ///
+    /// let params = UrlSearchParams::parse("limit=20&order=desc")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// let listing: Listing = params.deserialize_into().unwrap();
+    /// assert_eq!(listing.limit, 20);
+    /// assert_eq!(listing.order, Order::Desc);
+    /// assert!(!listing.archived);
+    /// ```
+    pub fn deserialize_into<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        T::deserialize(MapDeserializer::new(
+            self.entries()
+                .map(|(name, value)| (Part(name), Part(value))),
+        ))
+    }
+}
+
+impl UrlSearchParams {
+    /// Serializes `value`, which must be a struct or a map, into a new `UrlSearchParams` with
+    /// one pair per field or entry.
+    ///
+    /// Values are formatted from integers, floats, booleans, characters, strings, unit enum
+    /// variants and newtype structs. Fields set to `None` are skipped. As with
+    /// `serde_urlencoded`, sequences and nested structs are not supported.
+    ///
+    /// This method is only available if the `serde` Cargo feature is enabled.
+    ///
+    /// ```
+    /// use ada_url::UrlSearchParams;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// #[serde(rename_all = "lowercase")]
+    /// enum Order {
+    ///     Asc,
+    ///     Desc,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Listing {
+    ///     limit: usize,
+    ///     order: Order,
+    ///     cursor: Option<String>,
+    /// }
+    ///
+    /// let listing = Listing { limit: 20, order: Order::Desc, cursor: None };
+    /// let params = UrlSearchParams::from_serialize(&listing).unwrap();
+    /// assert_eq!(params.to_string(), "limit=20&order=desc");
+    /// ```
+    pub fn from_serialize<T: Serialize + ?Sized>(value: &T) -> Result<Self, Error> {
+        let mut params =
+            Self::parse("").map_err(|_| Error::custom("could not create search params"))?;
+        value.serialize(PairsSerializer {
+            params: &mut params,
+            name: None,
+        })?;
+        Ok(params)
+    }
+}
+
+/// Serializes a struct or a map into the pairs of a [`UrlSearchParams`].
+struct PairsSerializer<'a> {
+    params: &'a mut UrlSearchParams,
+    /// The name of the pair whose value is serialized next, for maps.
+    name: Option<String>,
+}
+
+fn top_level_error() -> Error {
+    Error::custom("a query can only be serialized from a struct or a map")
+}
+
+fn value_error() -> Error {
+    Error::custom("unsupported value in a query, expected a primitive or a unit variant")
+}
+
+macro_rules! serialize_unsupported {
+    ($error:ident; $($method:ident($($arg:ty),*) -> $ok:ty,)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<$ok, Self::Error> {
+                Err($error())
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for PairsSerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    serialize_unsupported! { top_level_error;
+        serialize_bool(bool) -> (),
+        serialize_i8(i8) -> (),
+        serialize_i16(i16) -> (),
+        serialize_i32(i32) -> (),
+        serialize_i64(i64) -> (),
+        serialize_u8(u8) -> (),
+        serialize_u16(u16) -> (),
+        serialize_u32(u32) -> (),
+        serialize_u64(u64) -> (),
+        serialize_f32(f32) -> (),
+        serialize_f64(f64) -> (),
+        serialize_char(char) -> (),
+        serialize_str(&str) -> (),
+        serialize_bytes(&[u8]) -> (),
+        serialize_unit_variant(&'static str, u32, &'static str) -> (),
+        serialize_seq(Option<usize>) -> Self::SerializeSeq,
+        serialize_tuple(usize) -> Self::SerializeTuple,
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct,
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant,
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant,
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), Error> {
+        Err(top_level_error())
+    }
+}
+
+impl PairsSerializer<'_> {
+    fn append<T: Serialize + ?Sized>(&mut self, name: &str, value: &T) -> Result<(), Error> {
+        if let Some(value) = value.serialize(PartSerializer)? {
+            self.params.append(name, &value);
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for PairsSerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.append(key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for PairsSerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        let name = key
+            .serialize(PartSerializer)?
+            .ok_or_else(|| Error::custom("a query name cannot be None"))?;
+        self.name = Some(name);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let name = self
+            .name
+            .take()
+            .ok_or_else(|| Error::custom("serialize_value called before serialize_key"))?;
+        self.append(&name, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Formats a name or value of a query, or returns `None` for values which are skipped.
+struct PartSerializer;
+
+macro_rules! serialize_to_string {
+    ($($method:ident($type:ty),)*) => {
+        $(
+            fn $method(self, value: $type) -> Result<Option<String>, Error> {
+                Ok(Some(value.to_string()))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for PartSerializer {
+    type Ok = Option<String>;
+    type Error = Error;
+    type SerializeSeq = Impossible<Option<String>, Error>;
+    type SerializeTuple = Impossible<Option<String>, Error>;
+    type SerializeTupleStruct = Impossible<Option<String>, Error>;
+    type SerializeTupleVariant = Impossible<Option<String>, Error>;
+    type SerializeMap = Impossible<Option<String>, Error>;
+    type SerializeStruct = Impossible<Option<String>, Error>;
+    type SerializeStructVariant = Impossible<Option<String>, Error>;
+
+    serialize_to_string! {
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Option<String>, Error> {
+        match core::str::from_utf8(value) {
+            Ok(value) => Ok(Some(value.into())),
+            Err(_) => Err(Error::custom("query values must be valid UTF-8")),
+        }
+    }
+
+    fn serialize_none(self) -> Result<Option<String>, Error> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Option<String>, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Option<String>, Error> {
+        Ok(Some(variant.into()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Option<String>, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Option<String>, Error> {
+        Err(value_error())
+    }
+
+    serialize_unsupported! { value_error;
+        serialize_unit() -> Option<String>,
+        serialize_unit_struct(&'static str) -> Option<String>,
+        serialize_seq(Option<usize>) -> Self::SerializeSeq,
+        serialize_tuple(usize) -> Self::SerializeTuple,
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct,
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant,
+        serialize_map(Option<usize>) -> Self::SerializeMap,
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct,
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant,
+    }
+}
+
+/// A name or value of a query, parsed into the type requested by the visitor.
+struct Part<'a>(&'a str);
+
+impl IntoDeserializer<'_, Error> for Part<'_> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+macro_rules! deserialize_from_str {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                match self.0.parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => Err(Error::invalid_value(Unexpected::Str(self.0), &visitor)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Part<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_str(self.0)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    deserialize_from_str! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Url, UrlSearchParams};
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Query {
+        name: String,
+        count: i32,
+        ratio: f64,
+        enabled: bool,
+        initial: char,
+        missing: Option<u8>,
+        present: Option<u8>,
+        id: Id,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Id(u64);
+
+    #[test]
+    fn should_deserialize_query() {
+        let url = Url::parse(
+            "https://example.com/?name=a+b%26c&count=-3&ratio=0.5&enabled=true&initial=%C3%A9&present=7&id=42&extra",
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            url.query_as::<Query>().unwrap(),
+            Query {
+                name: "a b&c".into(),
+                count: -3,
+                ratio: 0.5,
+                enabled: true,
+                initial: 'é',
+                missing: None,
+                present: Some(7),
+                id: Id(42),
+            }
+        );
+        let map: BTreeMap<String, String> = url.query_as().unwrap();
+        assert_eq!(map["extra"], "");
+        let empty: BTreeMap<String, String> = Url::parse("https://example.com/", None)
+            .unwrap()
+            .query_as()
+            .unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn should_report_invalid_queries() {
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Page {
+            page: u32,
+        }
+
+        let params = UrlSearchParams::parse("page=two").unwrap();
+        let error = params.deserialize_into::<Page>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid value: string \"two\", expected u32"
+        );
+        let params = UrlSearchParams::parse("").unwrap();
+        let error = params.deserialize_into::<Page>().unwrap_err();
+        assert_eq!(error.to_string(), "missing field `page`");
+        let params = UrlSearchParams::parse("page=1&page=2").unwrap();
+        let error = params.deserialize_into::<Page>().unwrap_err();
+        assert_eq!(error.to_string(), "duplicate field `page`");
+    }
+
+    #[test]
+    fn should_serialize_query() {
+        let query = Query {
+            name: "a b&c=d+é".into(),
+            count: -3,
+            ratio: 0.5,
+            enabled: true,
+            initial: '#',
+            missing: None,
+            present: Some(7),
+            id: Id(42),
+        };
+        let mut url = Url::parse("https://example.com/path?old=1#hash", None).unwrap();
+        url.set_query_from(&query).unwrap();
+        assert_eq!(
+            url.href(),
+            "https://example.com/path?name=a+b%26c%3Dd%2B%C3%A9&count=-3&ratio=0.5&enabled=true&initial=%23&present=7&id=42#hash"
+        );
+        assert_eq!(url.query_as::<Query>().unwrap(), query);
+
+        let map = BTreeMap::from([("b", Some("2")), ("a", Some("1")), ("c", None)]);
+        url.set_query_from(&map).unwrap();
+        assert_eq!(url.search(), "?a=1&b=2");
+        url.set_query_from(&BTreeMap::<String, String>::new())
+            .unwrap();
+        assert_eq!(url.href(), "https://example.com/path#hash");
+    }
+
+    #[test]
+    fn should_reject_unsupported_values() {
+        #[derive(Serialize)]
+        struct Nested {
+            inner: Id,
+            list: Vec<u8>,
+        }
+
+        let error = UrlSearchParams::from_serialize(&Nested {
+            inner: Id(1),
+            list: vec![1, 2],
+        })
+        .err()
+        .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "unsupported value in a query, expected a primitive or a unit variant"
+        );
+        let error = UrlSearchParams::from_serialize("a=1").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "a query can only be serialized from a struct or a map"
+        );
+    }
+}
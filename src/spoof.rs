@@ -0,0 +1,169 @@
+//! Detection of homograph risks in hostnames, following the mixed-script and confusable detection
+//! of [Unicode Technical Standard #39](https://www.unicode.org/reports/tr39/).
+//!
+//! Hostnames are converted to Unicode first, so Punycode labels are checked as they would be
+//! displayed.
+//!
+//! This module is only available if the `spoof` Cargo feature is enabled.
+//!
+//! ```
+//! use ada_url::{spoof, Url};
+//!
+//! let url = Url::parse("https://ѕсоре.com", None).expect("Invalid URL");
+//! assert_eq!(url.hostname(), "xn--e1argc3h.com");
+//! assert!(!spoof::is_display_safe(url.hostname()));
+//! assert!(spoof::is_confusable_with(url.hostname(), "scope.com"));
+//!
+//! assert!(spoof::is_display_safe("bücher.example"));
+//! ```
+
+use crate::{Idna, IdnaError, IdnaOptions};
+use alloc::string::String;
+use alloc::vec::Vec;
+use unicode_security::mixed_script::AugmentedScriptSet;
+use unicode_security::{skeleton, MixedScript};
+
+/// A homograph risk found in a label by [`check_host`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpoofRisk {
+    /// The label mixes characters of several scripts, e.g. Latin and Cyrillic in `pаypal`.
+    MixedScript {
+        /// The label, in Unicode.
+        label: String,
+    },
+    /// The label is written in a single script other than Latin, but every character is
+    /// confusable with an ASCII character, e.g. Cyrillic `ѕсоре` and `scope`.
+    WholeScriptConfusable {
+        /// The label, in Unicode.
+        label: String,
+        /// The ASCII skeleton the label is confusable with, as defined by UTS #39.
+        skeleton: String,
+    },
+}
+
+/// Returns the homograph risks of every label of a hostname, as returned by
+/// [`Url::hostname`](crate::Url::hostname). Returns an error if the hostname is not a valid
+/// domain.
+///
+/// IP addresses and ASCII labels carry no risk.
+///
+/// ```
+/// use ada_url::spoof::{self, SpoofRisk};
+///
+/// assert_eq!(
+///     spoof::check_host("xn--pypal-4ve.com").unwrap(),
+///     [SpoofRisk::MixedScript {
+///         label: "pаypal".to_owned()
+///     }]
+/// );
+/// ```
+pub fn check_host(host: &str) -> Result<Vec<SpoofRisk>, IdnaError> {
+    if host.starts_with('[') {
+        return Ok(Vec::new());
+    }
+    let unicode = Idna::to_unicode_with(host, IdnaOptions::default())?;
+    Ok(unicode
+        .split('.')
+        .filter(|label| !label.is_ascii())
+        .filter_map(check_label)
+        .collect())
+}
+
+/// Returns `true` if the hostname is a valid domain or an IP address, and none of its labels
+/// carries a homograph risk. See [`check_host`].
+#[must_use]
+pub fn is_display_safe(host: &str) -> bool {
+    check_host(host).is_ok_and(|risks| risks.is_empty())
+}
+
+/// Returns `true` if the two hostnames are different but can be confused with each other, e.g.
+/// `xn--e1argc3h.com` (`ѕсоре.com`) and `scope.com`, or `paypa1.com` and `paypal.com`.
+///
+/// Hostnames are compared by their skeleton, as defined by UTS #39.
+#[must_use]
+pub fn is_confusable_with(host: &str, expected: &str) -> bool {
+    let host = to_unicode(host);
+    let expected = to_unicode(expected);
+    host != expected && skeleton(&host).eq(skeleton(&expected))
+}
+
+fn to_unicode(host: &str) -> String {
+    Idna::to_unicode_with(host, IdnaOptions::default()).unwrap_or_else(|_| host.into())
+}
+
+fn check_label(label: &str) -> Option<SpoofRisk> {
+    if !label.is_single_script() {
+        return Some(SpoofRisk::MixedScript {
+            label: label.into(),
+        });
+    }
+    let scripts = AugmentedScriptSet::for_str(label);
+    let mut latin = scripts;
+    latin.intersect_with(AugmentedScriptSet::for_char('a'));
+    let skeleton: String = skeleton(label).collect();
+    if latin.is_empty() && skeleton.is_ascii() {
+        return Some(SpoofRisk::WholeScriptConfusable {
+            label: label.into(),
+            skeleton,
+        });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spoof::{check_host, is_confusable_with, is_display_safe, SpoofRisk};
+
+    #[test]
+    fn should_accept_single_script_labels() {
+        for host in [
+            "example.com",
+            "bücher.example",
+            "xn--bcher-kva.example",
+            "пример.рф",
+            "日本語.jp",
+            "ドメイン名例.jp",
+            "한국어.kr",
+            "127.0.0.1",
+            "[::1]",
+        ] {
+            assert!(is_display_safe(host), "{host}");
+        }
+    }
+
+    #[test]
+    fn should_flag_mixed_script_labels() {
+        assert_eq!(
+            check_host("login.pаypal.com").unwrap(),
+            [SpoofRisk::MixedScript {
+                label: "pаypal".into()
+            }]
+        );
+    }
+
+    #[test]
+    fn should_flag_whole_script_confusables() {
+        assert_eq!(
+            check_host("xn--e1argc3h.com").unwrap(),
+            [SpoofRisk::WholeScriptConfusable {
+                label: "ѕсоре".into(),
+                skeleton: "scope".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_domains() {
+        assert!(check_host("xn--zz.com").is_err());
+        assert!(!is_display_safe("xn--zz.com"));
+    }
+
+    #[test]
+    fn should_detect_confusable_hosts() {
+        assert!(is_confusable_with("ѕсоре.com", "scope.com"));
+        assert!(is_confusable_with("xn--e1argc3h.com", "SCOPE.com"));
+        assert!(is_confusable_with("paypa1.com", "paypal.com"));
+        assert!(!is_confusable_with("scope.com", "SCOPE.com"));
+        assert!(!is_confusable_with("scope.com", "slope.com"));
+    }
+}
@@ -0,0 +1,169 @@
+//! [`proptest`](https://docs.rs/proptest) strategies that generate valid URLs.
+//!
+//! Strategies are provided per component, so they can be combined to generate inputs for a
+//! specific use case. [`url()`] combines all of them, and is also used to implement
+//! [`Arbitrary`] for [`Url`].
+//!
+//! This module is only available if the `proptest` Cargo feature is enabled.
+//!
+//! ```
+//! use ada_url::{strategy, Url};
+//! use proptest::prelude::*;
+//!
+//! proptest!(|(url in strategy::url())| {
+//!     prop_assert_eq!(Url::parse(url.href(), None).unwrap(), url);
+//! });
+//!
+//! proptest!(|(url in any::<Url>())| {
+//!     prop_assert!(url.has_hostname());
+//! });
+//! ```
+
+use crate::generate::{IDN_LABELS, SPECIAL_SCHEMES};
+use crate::Url;
+use proptest::arbitrary::Arbitrary;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::strategy::BoxedStrategy;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Generates special schemes (e.g. `https`) and syntactically valid custom schemes.
+pub fn scheme() -> impl Strategy<Value = String> {
+    prop_oneof![
+        3 => proptest::sample::select(SPECIAL_SCHEMES).prop_map(str::to_owned),
+        1 => "[a-z][a-z0-9+.-]{0,8}",
+    ]
+}
+
+/// Generates special schemes, e.g. `https`, for which a host is always required.
+pub fn special_scheme() -> impl Strategy<Value = String> {
+    proptest::sample::select(SPECIAL_SCHEMES).prop_map(str::to_owned)
+}
+
+/// Generates IPv4 hosts in dotted-decimal notation.
+pub fn ipv4_host() -> impl Strategy<Value = String> {
+    any::<u32>().prop_map(|address| Ipv4Addr::from(address).to_string())
+}
+
+/// Generates bracketed IPv6 hosts, e.g. `[::1]`.
+pub fn ipv6_host() -> impl Strategy<Value = String> {
+    any::<u128>().prop_map(|address| format!("[{}]", Ipv6Addr::from(address)))
+}
+
+/// Generates ASCII domains made of one to four labels and an alphabetic top-level domain.
+pub fn domain() -> impl Strategy<Value = String> {
+    (
+        vec("[a-z0-9]([a-z0-9-]{0,10}[a-z0-9])?", 0..4),
+        "[a-z]{2,6}",
+    )
+        .prop_map(|(mut labels, tld)| {
+            labels.push(tld);
+            labels.join(".")
+        })
+}
+
+/// Generates internationalized domains, mixing Unicode and ASCII labels.
+pub fn idn_host() -> impl Strategy<Value = String> {
+    let label = prop_oneof![
+        proptest::sample::select(IDN_LABELS).prop_map(str::to_owned),
+        "[a-zà-öø-ÿ]{1,8}",
+    ];
+    (vec(label, 1..4), "[a-z]{2,6}").prop_map(|(mut labels, tld)| {
+        labels.push(tld);
+        labels.join(".")
+    })
+}
+
+/// Generates any host accepted by special schemes.
+pub fn host() -> impl Strategy<Value = String> {
+    prop_oneof![
+        3 => domain(),
+        1 => idn_host(),
+        1 => ipv4_host(),
+        1 => ipv6_host(),
+    ]
+}
+
+/// Generates weird-but-valid paths, including dot segments, percent-encoded bytes, reserved
+/// characters and Unicode. The path is either empty or starts with `/`.
+pub fn path() -> impl Strategy<Value = String> {
+    let segment = prop_oneof![
+        4 => "[a-zA-Z0-9._~-]{0,10}",
+        1 => Just(".".to_owned()),
+        1 => Just("..".to_owned()),
+        1 => Just("%2e".to_owned()),
+        1 => "%[0-9A-Fa-f]{2}",
+        1 => "[!$&'()*+,;=:@ ]{1,3}",
+        1 => "[é日本😀]{1,3}",
+    ];
+    vec(segment, 0..6).prop_map(|segments| {
+        segments.iter().fold(String::new(), |mut path, segment| {
+            path.push('/');
+            path.push_str(segment);
+            path
+        })
+    })
+}
+
+/// Generates URLs with a special scheme, an optional port, a path, an optional query and an
+/// optional fragment.
+pub fn url() -> impl Strategy<Value = Url> {
+    let port = proptest::option::of(any::<u16>());
+    let query = proptest::option::of("[a-zA-Z0-9=&%+ ]{0,20}");
+    let fragment = proptest::option::of("[a-zA-Z0-9 /?#]{0,10}");
+    (special_scheme(), host(), port, path(), query, fragment).prop_filter_map(
+        "generated input should be a valid URL",
+        |(scheme, host, port, path, query, fragment)| {
+            let mut input = format!("{scheme}://{host}");
+            if let Some(port) = port {
+                input.push_str(&format!(":{port}"));
+            }
+            input.push_str(&path);
+            if let Some(query) = query {
+                input.push_str(&format!("?{query}"));
+            }
+            if let Some(fragment) = fragment {
+                input.push_str(&format!("#{fragment}"));
+            }
+            Url::parse(input, None).ok()
+        },
+    )
+}
+
+impl Arbitrary for Url {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        url().boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HostType, Url};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn should_round_trip(url in super::url()) {
+            prop_assert_eq!(Url::parse(url.href(), None).unwrap(), url);
+        }
+
+        #[test]
+        fn should_generate_hosts(ipv4 in super::ipv4_host(), ipv6 in super::ipv6_host(), domain in super::idn_host()) {
+            let url = Url::parse(format!("http://{ipv4}"), None).unwrap();
+            prop_assert_eq!(url.host_type(), HostType::IPV4);
+            let url = Url::parse(format!("http://{ipv6}"), None).unwrap();
+            prop_assert_eq!(url.host_type(), HostType::IPV6);
+            let url = Url::parse(format!("http://{domain}"), None).unwrap();
+            prop_assert_eq!(url.host_type(), HostType::Domain);
+        }
+
+        #[test]
+        fn should_generate_custom_schemes(scheme in super::scheme(), path in super::path()) {
+            let input = format!("{scheme}://example.com{path}");
+            prop_assert!(Url::can_parse(&input, None));
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use crate::{ParseUrlError, Url};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+const NIL: usize = usize::MAX;
+
+/// Hit statistics of a [`UrlCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UrlCacheStats {
+    /// Number of lookups answered from the cache.
+    pub hits: u64,
+    /// Number of lookups that required parsing the input.
+    pub misses: u64,
+    /// Number of entries evicted to make room for new ones.
+    pub evictions: u64,
+}
+
+impl UrlCacheStats {
+    /// Returns the ratio of hits over all lookups, or `0.0` if there were no lookups.
+    #[must_use]
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+struct Node {
+    key: Box<str>,
+    // `None` memoizes inputs that failed to parse.
+    value: Option<Url>,
+    prev: usize,
+    next: usize,
+}
+
+/// Least recently used list backed by a slab of nodes, so that lookups, promotions and
+/// evictions are all O(1).
+struct Lru {
+    map: HashMap<Box<str>, usize>,
+    nodes: Vec<Node>,
+    head: usize,
+    tail: usize,
+    stats: UrlCacheStats,
+}
+
+impl Lru {
+    fn unlink(&mut self, index: usize) {
+        let (prev, next) = (self.nodes[index].prev, self.nodes[index].next);
+        match prev {
+            NIL => self.head = next,
+            prev => self.nodes[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.nodes[next].prev = prev,
+        }
+    }
+
+    fn push_front(&mut self, index: usize) {
+        self.nodes[index].prev = NIL;
+        self.nodes[index].next = self.head;
+        match self.head {
+            NIL => self.tail = index,
+            head => self.nodes[head].prev = index,
+        }
+        self.head = index;
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.nodes.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+}
+
+/// A thread-safe, fixed-capacity cache of parsed URLs keyed by their input string.
+///
+/// Once the cache is full, the least recently used entry is evicted. Inputs that fail to
+/// parse are memoized as well, so repeatedly parsing the same invalid input is also cheap.
+///
+/// This type is only available if the `std` Cargo feature is enabled.
+///
+/// ```
+/// use ada_url::UrlCache;
+///
+/// let cache = UrlCache::new(128);
+/// let url = cache.parse("https://example.com/foo").expect("Invalid URL");
+/// assert_eq!(url.href(), "https://example.com/foo");
+/// assert!(cache.parse("not a url").is_err());
+///
+/// cache.parse("https://example.com/foo").unwrap();
+/// assert_eq!(cache.stats().hits, 1);
+/// assert_eq!(cache.stats().misses, 2);
+/// ```
+pub struct UrlCache {
+    capacity: usize,
+    inner: Mutex<Lru>,
+}
+
+impl UrlCache {
+    /// Creates an empty cache that holds at most `capacity` entries.
+    ///
+    /// A capacity of zero disables caching, but statistics are still recorded. Memory is
+    /// allocated as entries are inserted, so the capacity is only an upper bound.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(Lru {
+                map: HashMap::new(),
+                nodes: Vec::new(),
+                head: NIL,
+                tail: NIL,
+                stats: UrlCacheStats::default(),
+            }),
+        }
+    }
+
+    /// Parses the input, returning a copy of the cached result if the same input was
+    /// parsed before.
+    ///
+    /// ```
+    /// use ada_url::UrlCache;
+    ///
+    /// let cache = UrlCache::new(1);
+    /// cache.parse("https://a.com").unwrap();
+    /// cache.parse("https://b.com").unwrap();
+    /// assert_eq!(cache.len(), 1);
+    /// assert_eq!(cache.stats().evictions, 1);
+    /// ```
+    pub fn parse<'input>(&self, input: &'input str) -> Result<Url, ParseUrlError<&'input str>> {
+        let mut lru = self.lock();
+
+        if let Some(&index) = lru.map.get(input) {
+            lru.stats.hits += 1;
+            lru.unlink(index);
+            lru.push_front(index);
+            return lru.nodes[index]
+                .value
+                .clone()
+                .ok_or(ParseUrlError { input });
+        }
+
+        lru.stats.misses += 1;
+        let value = Url::parse(input, None).ok();
+        if self.capacity == 0 {
+            return value.ok_or(ParseUrlError { input });
+        }
+
+        let node = Node {
+            key: input.into(),
+            value: value.clone(),
+            prev: NIL,
+            next: NIL,
+        };
+        let index = if lru.nodes.len() < self.capacity {
+            lru.nodes.push(node);
+            lru.nodes.len() - 1
+        } else {
+            // Reuse the slot of the least recently used entry.
+            let index = lru.tail;
+            lru.unlink(index);
+            let evicted = core::mem::replace(&mut lru.nodes[index], node);
+            lru.map.remove(&evicted.key);
+            lru.stats.evictions += 1;
+            index
+        };
+        let key = lru.nodes[index].key.clone();
+        lru.map.insert(key, index);
+        lru.push_front(index);
+
+        value.ok_or(ParseUrlError { input })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Lru> {
+        // The list is always left consistent, so a poisoned lock is safe to reuse.
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Returns the maximum number of entries held by the cache.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of entries currently held by the cache.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().nodes.len()
+    }
+
+    /// Returns true if the cache holds no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the hit statistics recorded since creation or the last [`UrlCache::reset_stats`].
+    #[must_use]
+    pub fn stats(&self) -> UrlCacheStats {
+        self.lock().stats
+    }
+
+    /// Resets the hit statistics to zero without touching the cached entries.
+    pub fn reset_stats(&self) {
+        self.lock().stats = UrlCacheStats::default();
+    }
+
+    /// Removes all entries from the cache. Statistics are kept.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+impl core::fmt::Debug for UrlCache {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("UrlCache")
+            .field("capacity", &self.capacity)
+            .field("len", &self.len())
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::UrlCache;
+
+    #[test]
+    fn should_evict_least_recently_used() {
+        let cache = UrlCache::new(2);
+        cache.parse("https://a.com").unwrap();
+        cache.parse("https://b.com").unwrap();
+        // Promote `a`, so `b` becomes the least recently used entry.
+        cache.parse("https://a.com").unwrap();
+        cache.parse("https://c.com").unwrap();
+        assert_eq!(cache.len(), 2);
+
+        cache.reset_stats();
+        cache.parse("https://a.com").unwrap();
+        cache.parse("https://c.com").unwrap();
+        assert_eq!(cache.stats().hits, 2);
+        cache.parse("https://b.com").unwrap();
+        assert_eq!(cache.stats().misses, 1);
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn should_memoize_errors() {
+        let cache = UrlCache::new(4);
+        assert_eq!(cache.parse("invalid").unwrap_err().input, "invalid");
+        assert_eq!(cache.parse("invalid").unwrap_err().input, "invalid");
+        assert_eq!(cache.stats().hits, 1);
+        assert_eq!(cache.stats().hit_rate(), 0.5);
+    }
+
+    #[test]
+    fn should_allocate_lazily() {
+        let cache = UrlCache::new(usize::MAX);
+        cache.parse("https://example.com").unwrap();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.capacity(), usize::MAX);
+    }
+
+    #[test]
+    fn should_not_cache_with_zero_capacity() {
+        let cache = UrlCache::new(0);
+        cache.parse("https://a.com").unwrap();
+        cache.parse("https://a.com").unwrap();
+        assert!(cache.is_empty());
+        assert_eq!(cache.stats().misses, 2);
+        cache.clear();
+    }
+}
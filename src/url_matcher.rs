@@ -0,0 +1,247 @@
+use crate::{Host, HostType, ParseHostError, ParseUrlError, Url};
+use std::collections::HashSet;
+use std::string::String;
+
+/// A set of rules matching URLs by origin, host suffix, path prefix, scheme or port, e.g. to
+/// implement the allowlist of a proxy or the denylist of a webhook validator.
+///
+/// A URL matches if any rule matches it. Every rule is looked up in a hash set, so matching a
+/// URL costs a few lookups per label of its host and segment of its path, regardless of the
+/// number of rules. To require several conditions at once, e.g. a host and a scheme, check
+/// the URL against several matchers.
+///
+/// This type is only available if the `std` Cargo feature is enabled.
+///
+/// ```
+/// use ada_url::{Url, UrlMatcher};
+///
+/// let mut matcher = UrlMatcher::new();
+/// matcher.add_host_suffix("example.com").unwrap();
+/// matcher.add_origin("http://localhost:8080").unwrap();
+/// matcher.add_path_prefix("/webhooks");
+///
+/// let matches = |input| matcher.matches(&Url::parse(input, None).expect("Invalid URL"));
+/// assert!(matches("https://api.EXAMPLE.com/"));
+/// assert!(matches("http://localhost:8080/a"));
+/// assert!(matches("https://example.org/webhooks/1"));
+/// assert!(!matches("https://notexample.com/"));
+/// assert!(!matches("http://localhost:8081/"));
+/// assert!(!matches("https://example.org/webhooks2"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UrlMatcher {
+    origins: HashSet<String>,
+    host_suffixes: HashSet<String>,
+    path_prefixes: HashSet<String>,
+    schemes: HashSet<String>,
+    ports: HashSet<u16>,
+}
+
+impl UrlMatcher {
+    /// Creates a matcher without rules, which matches no URL.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if the matcher has no rules.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.origins.is_empty()
+            && self.host_suffixes.is_empty()
+            && self.path_prefixes.is_empty()
+            && self.schemes.is_empty()
+            && self.ports.is_empty()
+    }
+
+    /// Matches URLs with the same origin as `origin`, i.e. the same scheme, host and port,
+    /// where a default port is equivalent to no port.
+    ///
+    /// Fails if `origin` is not a valid URL or has an opaque origin, e.g. `file:///`.
+    ///
+    /// ```
+    /// use ada_url::{Url, UrlMatcher};
+    ///
+    /// let mut matcher = UrlMatcher::new();
+    /// matcher.add_origin("https://example.com:443/ignored").unwrap();
+    /// assert!(matcher.matches(&Url::parse("https://example.com/a", None).unwrap()));
+    /// assert!(!matcher.matches(&Url::parse("http://example.com/a", None).unwrap()));
+    /// assert!(matcher.add_origin("file:///").is_err());
+    /// ```
+    pub fn add_origin<'a>(&mut self, origin: &'a str) -> Result<(), ParseUrlError<&'a str>> {
+        let serialized = Url::parse(origin, None)?.origin();
+        if serialized == "null" {
+            return Err(ParseUrlError { input: origin });
+        }
+        self.origins.insert(serialized);
+        Ok(())
+    }
+
+    /// Matches URLs whose host is `suffix` or a subdomain of it, e.g. `example.com` matches
+    /// `example.com` and `www.example.com`, but not `notexample.com`. IP addresses only match
+    /// themselves.
+    ///
+    /// The suffix is parsed as the host of a special URL, so it is converted to ASCII and
+    /// lowercased, and a leading or trailing dot is ignored. Fails if it is not a valid host or
+    /// has a port.
+    ///
+    /// ```
+    /// use ada_url::{Url, UrlMatcher};
+    ///
+    /// let mut matcher = UrlMatcher::new();
+    /// matcher.add_host_suffix(".BÜCHER.de").unwrap();
+    /// assert!(matcher.matches(&Url::parse("https://shop.xn--bcher-kva.de/", None).unwrap()));
+    /// assert!(matcher.add_host_suffix("example.com:443").is_err());
+    /// ```
+    pub fn add_host_suffix<'a>(&mut self, suffix: &'a str) -> Result<(), ParseHostError<&'a str>> {
+        let host = Host::parse(suffix.strip_prefix('.').unwrap_or(suffix), true)
+            .ok()
+            .filter(|host| host.port().is_none())
+            .ok_or(ParseHostError { input: suffix })?;
+        let hostname = host.hostname();
+        let hostname = hostname.strip_suffix('.').unwrap_or(hostname);
+        self.host_suffixes.insert(hostname.into());
+        Ok(())
+    }
+
+    /// Matches URLs whose path starts with `prefix` at a segment boundary, e.g. `/api` matches
+    /// `/api` and `/api/users`, but not `/apis`. A prefix ending with a slash, such as `/api/`,
+    /// only matches paths below it.
+    ///
+    /// The prefix is compared with the serialized, percent-encoded path.
+    pub fn add_path_prefix(&mut self, prefix: &str) {
+        self.path_prefixes.insert(prefix.into());
+    }
+
+    /// Matches URLs with the given scheme, e.g. `https`, with or without a trailing colon.
+    pub fn add_scheme(&mut self, scheme: &str) {
+        let scheme = scheme.strip_suffix(':').unwrap_or(scheme);
+        self.schemes.insert(scheme.to_ascii_lowercase());
+    }
+
+    /// Matches URLs with the given port, or with a scheme whose default port it is.
+    ///
+    /// ```
+    /// use ada_url::{Url, UrlMatcher};
+    ///
+    /// let mut matcher = UrlMatcher::new();
+    /// matcher.add_port(443);
+    /// assert!(matcher.matches(&Url::parse("https://example.com/", None).unwrap()));
+    /// assert!(matcher.matches(&Url::parse("http://example.com:443/", None).unwrap()));
+    /// ```
+    pub fn add_port(&mut self, port: u16) {
+        self.ports.insert(port);
+    }
+
+    /// Returns true if any rule matches `url`.
+    #[must_use]
+    pub fn matches(&self, url: &Url) -> bool {
+        self.matches_scheme(url)
+            || self.matches_port(url)
+            || self.matches_host(url)
+            || self.matches_path(url)
+            || self.matches_origin(url)
+    }
+
+    fn matches_scheme(&self, url: &Url) -> bool {
+        let scheme = url.protocol();
+        !self.schemes.is_empty() && self.schemes.contains(&scheme[..scheme.len() - 1])
+    }
+
+    fn matches_port(&self, url: &Url) -> bool {
+        url.port_or_known_default()
+            .is_some_and(|port| self.ports.contains(&port))
+    }
+
+    fn matches_host(&self, url: &Url) -> bool {
+        if self.host_suffixes.is_empty() {
+            return false;
+        }
+        let hostname = url.hostname();
+        if url.host_type() != HostType::Domain {
+            return self.host_suffixes.contains(hostname);
+        }
+        // A fully qualified domain, with a trailing dot, is the same as its relative form.
+        let hostname = hostname.strip_suffix('.').unwrap_or(hostname);
+        let parents = hostname.match_indices('.').map(|(i, _)| &hostname[i + 1..]);
+        core::iter::once(hostname)
+            .chain(parents)
+            .any(|suffix| self.host_suffixes.contains(suffix))
+    }
+
+    fn matches_path(&self, url: &Url) -> bool {
+        if self.path_prefixes.is_empty() {
+            return false;
+        }
+        let path = url.pathname();
+        let boundaries = path
+            .match_indices('/')
+            .flat_map(|(i, _)| [&path[..i], &path[..=i]]);
+        core::iter::once(path)
+            .chain(boundaries)
+            .any(|prefix| self.path_prefixes.contains(prefix))
+    }
+
+    fn matches_origin(&self, url: &Url) -> bool {
+        !self.origins.is_empty() && self.origins.contains(&url.origin())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Url, UrlMatcher};
+
+    fn matches(matcher: &UrlMatcher, input: &str) -> bool {
+        matcher.matches(&Url::parse(input, None).unwrap())
+    }
+
+    #[test]
+    fn should_match_nothing_without_rules() {
+        let matcher = UrlMatcher::new();
+        assert!(matcher.is_empty());
+        assert!(!matches(&matcher, "https://example.com/"));
+    }
+
+    #[test]
+    fn should_match_host_suffixes() {
+        let mut matcher = UrlMatcher::new();
+        matcher.add_host_suffix("example.com.").unwrap();
+        matcher.add_host_suffix("127.1").unwrap();
+        matcher.add_host_suffix("[::1]").unwrap();
+        assert!(matches(&matcher, "https://example.com/"));
+        assert!(matches(&matcher, "https://a.b.example.com./"));
+        assert!(matches(&matcher, "ws://127.0.0.1:9000/"));
+        assert!(matches(&matcher, "http://[0::1]/"));
+        assert!(!matches(&matcher, "https://example.com.evil/"));
+        assert!(!matches(&matcher, "https://xexample.com/"));
+        assert!(!matches(&matcher, "foo://a.example.com/"));
+        assert!(matcher.add_host_suffix("").is_err());
+        assert!(matcher.add_host_suffix("exa mple.com").is_err());
+    }
+
+    #[test]
+    fn should_match_path_prefixes_at_segment_boundaries() {
+        let mut matcher = UrlMatcher::new();
+        matcher.add_path_prefix("/api");
+        matcher.add_path_prefix("/static/");
+        assert!(matches(&matcher, "https://example.com/api"));
+        assert!(matches(&matcher, "https://example.com/api/"));
+        assert!(matches(&matcher, "https://example.com/api/v1?q"));
+        assert!(matches(&matcher, "https://example.com/static/a.css"));
+        assert!(!matches(&matcher, "https://example.com/apis"));
+        assert!(!matches(&matcher, "https://example.com/static"));
+        assert!(!matches(&matcher, "https://example.com/v1/api"));
+    }
+
+    #[test]
+    fn should_match_schemes_and_ports() {
+        let mut matcher = UrlMatcher::new();
+        matcher.add_scheme("DATA:");
+        matcher.add_port(22);
+        assert!(matches(&matcher, "data:text/plain,a"));
+        assert!(matches(&matcher, "ssh://example.com:22/"));
+        assert!(matches(&matcher, "ftp://example.com:22/"));
+        assert!(!matches(&matcher, "ftp://example.com/"));
+        assert!(!matches(&matcher, "https://example.com/"));
+    }
+}
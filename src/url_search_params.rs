@@ -1,7 +1,48 @@
-use crate::{ffi, ParseUrlError};
+use crate::{ffi, ParseUrlError, Url};
+
+/// How [`UrlSearchParams::merge`] combines the pairs of a key present in both instances.
+///
+/// This type is only available if the `alloc` Cargo feature is enabled.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MergeStrategy {
+    /// Keeps the existing pairs and appends the merged ones after them.
+    #[default]
+    Append,
+    /// Replaces the existing values of each merged key, keeping the position of its first pair.
+    Overwrite,
+}
+
+/// Which value [`UrlSearchParams::to_single_value_map`] keeps for a repeated key.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DuplicatePolicy {
+    /// Keeps the first value, like [`UrlSearchParams::get`].
+    #[default]
+    FirstWins,
+    /// Keeps the last value.
+    LastWins,
+}
 
 #[derive(Hash)]
-pub struct UrlSearchParams(*mut ffi::ada_url_search_params);
+pub struct UrlSearchParams(pub(crate) *mut ffi::ada_url_search_params);
+
+/// Deep-copies the pairs through an FFI call, so that the clone can be modified independently.
+///
+/// ```
+/// use ada_url::UrlSearchParams;
+/// let template = UrlSearchParams::parse("a=1&b=2")
+///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+/// let mut params = template.clone();
+/// params.set("a", "3");
+/// assert_eq!(template.to_string(), "a=1&b=2");
+/// assert_eq!(params.to_string(), "a=3&b=2");
+/// ```
+impl Clone for UrlSearchParams {
+    fn clone(&self) -> Self {
+        Self(unsafe { ffi::ada_search_params_copy(self.0) })
+    }
+}
 
 impl Drop for UrlSearchParams {
     fn drop(&mut self) {
@@ -28,6 +69,27 @@ impl UrlSearchParams {
         }))
     }
 
+    /// Returns the pairs of the query of a URL, which are empty if the URL has no query or an
+    /// empty one.
+    ///
+    /// ```
+    /// use ada_url::{Url, UrlSearchParams};
+    /// let url = Url::parse("https://example.com/?a=1&b=x+y#c", None).expect("Invalid URL");
+    /// let params = UrlSearchParams::from_url(&url);
+    /// assert_eq!(params.get("b"), Some("x y"));
+    ///
+    /// let url = Url::parse("https://example.com/??a=1", None).expect("Invalid URL");
+    /// assert_eq!(UrlSearchParams::from_url(&url).get("?a"), Some("1"));
+    ///
+    /// let url = Url::parse("https://example.com/?", None).expect("Invalid URL");
+    /// assert!(UrlSearchParams::from_url(&url).is_empty());
+    /// ```
+    pub fn from_url(url: &Url) -> Self {
+        // The parser strips the leading `?` of `search`, and only that one.
+        UrlSearchParams::parse(url.search())
+            .expect("Should be able to parse the query of a URL. This is likely due to a bug")
+    }
+
     /// Returns the unique keys in a UrlSearchParams.
     ///
     /// ```
@@ -52,6 +114,22 @@ impl UrlSearchParams {
         unsafe { ffi::ada_search_params_sort(self.0) }
     }
 
+    /// Removes all key/value pairs, keeping the UrlSearchParams struct and its allocation
+    /// for reuse.
+    ///
+    /// ```
+    /// use ada_url::UrlSearchParams;
+    /// let mut params = UrlSearchParams::parse("a=1&b=2")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// params.clear();
+    /// assert!(params.is_empty());
+    /// params.append("c", "3");
+    /// assert_eq!(params.to_string(), "c=3");
+    /// ```
+    pub fn clear(&mut self) {
+        unsafe { ffi::ada_search_params_reset(self.0, "".as_ptr().cast(), 0) }
+    }
+
     /// Appends a key/value to the UrlSearchParams struct.
     pub fn append(&mut self, key: &str, value: &str) {
         unsafe {
@@ -65,6 +143,43 @@ impl UrlSearchParams {
         }
     }
 
+    /// Appends many key/value pairs, in order, crossing the FFI boundary once rather than once
+    /// per pair like [`UrlSearchParams::append`].
+    ///
+    /// This method is only available if the `alloc` Cargo feature is enabled.
+    ///
+    /// ```
+    /// use ada_url::UrlSearchParams;
+    /// let mut params = UrlSearchParams::parse("a=1")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// params.append_pairs([("b", "2"), ("a", "x y")]);
+    /// assert_eq!(params.to_string(), "a=1&b=2&a=x+y");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn append_pairs<K, V, I>(&mut self, pairs: I)
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut buffer = alloc::string::String::new();
+        let mut lengths = alloc::vec::Vec::new();
+        for (key, value) in pairs {
+            let (key, value) = (key.as_ref(), value.as_ref());
+            buffer.push_str(key);
+            buffer.push_str(value);
+            lengths.extend([key.len(), value.len()]);
+        }
+        unsafe {
+            ffi::ada_search_params_append_many(
+                self.0,
+                buffer.as_ptr().cast(),
+                lengths.as_ptr(),
+                lengths.len() / 2,
+            )
+        }
+    }
+
     /// Removes all pre-existing keys from the UrlSearchParams struct
     /// and appends the new key/value.
     ///
@@ -87,6 +202,60 @@ impl UrlSearchParams {
         }
     }
 
+    /// Merges the key/value pairs of `other` into this UrlSearchParams struct, in order.
+    ///
+    /// This method is only available if the `alloc` Cargo feature is enabled.
+    ///
+    /// ```
+    /// use ada_url::{MergeStrategy, UrlSearchParams};
+    /// let defaults = UrlSearchParams::parse("page=1&limit=10")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// let overrides = UrlSearchParams::parse("page=2&tag=a&tag=b")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    ///
+    /// let mut params = UrlSearchParams::parse("")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// params.merge(&defaults, MergeStrategy::Append);
+    /// params.merge(&overrides, MergeStrategy::Overwrite);
+    /// assert_eq!(params.to_string(), "page=2&limit=10&tag=a&tag=b");
+    ///
+    /// params.merge(&overrides, MergeStrategy::Append);
+    /// assert_eq!(params.to_string(), "page=2&limit=10&tag=a&tag=b&page=2&tag=a&tag=b");
+    ///
+    /// params.merge(&overrides, MergeStrategy::Overwrite);
+    /// assert_eq!(params.to_string(), "page=2&limit=10&tag=a&tag=b");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn merge(&mut self, other: &UrlSearchParams, strategy: MergeStrategy) {
+        if strategy == MergeStrategy::Append {
+            for (key, value) in other.entries() {
+                self.append(key, value);
+            }
+            return;
+        }
+        // Like `set`, the first merged value of a key replaces its existing pairs at the position
+        // of the first one, and the following values are appended.
+        let mut first_values = alloc::collections::BTreeMap::new();
+        for (key, value) in other.entries() {
+            first_values.entry(key).or_insert(value);
+        }
+        let mut replaced = alloc::collections::BTreeSet::new();
+        let mut merged = alloc::vec::Vec::new();
+        for (key, value) in self.entries() {
+            match first_values.get(key) {
+                None => merged.push((key, value)),
+                Some(&first) if replaced.insert(key) => merged.push((key, first)),
+                Some(_) => {}
+            }
+        }
+        for (key, value) in other.entries() {
+            if !replaced.remove(key) {
+                merged.push((key, value));
+            }
+        }
+        *self = Self::from_pairs(merged);
+    }
+
     /// Removes a key from the UrlSearchParams struct.
     ///
     /// ```
@@ -153,6 +322,20 @@ impl UrlSearchParams {
         }
     }
 
+    /// Returns whether a pair matches the key, compared ASCII case-insensitively, and the value.
+    ///
+    /// ```
+    /// use ada_url::UrlSearchParams;
+    /// let params = UrlSearchParams::parse("Lang=fr&b=2")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// assert!(params.contains_ignore_case("LANG", "fr"));
+    /// assert!(!params.contains_ignore_case("lang", "FR"));
+    /// ```
+    pub fn contains_ignore_case(&self, key: &str, value: &str) -> bool {
+        self.entries()
+            .any(|(k, v)| k.eq_ignore_ascii_case(key) && v == value)
+    }
+
     /// Returns the value of the key.
     ///
     /// ```
@@ -173,6 +356,50 @@ impl UrlSearchParams {
         }
     }
 
+    /// Returns the value of the first pair whose key matches, compared ASCII case-insensitively.
+    ///
+    /// ```
+    /// use ada_url::UrlSearchParams;
+    /// let params = UrlSearchParams::parse("Lang=fr&LANG=en")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// assert_eq!(params.get_ignore_case("lang"), Some("fr"));
+    /// assert_eq!(params.get_ignore_case("länG"), None);
+    /// ```
+    pub fn get_ignore_case(&self, key: &str) -> Option<&str> {
+        self.entries()
+            .find_map(|(k, v)| k.eq_ignore_ascii_case(key).then_some(v))
+    }
+
+    /// Parses the value of the key, or returns `None` if the key is missing.
+    ///
+    /// ```
+    /// use ada_url::UrlSearchParams;
+    /// let params = UrlSearchParams::parse("page=2&debug=true&size=big")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// assert_eq!(params.get_parsed::<u32>("page"), Some(Ok(2)));
+    /// assert_eq!(params.get_parsed::<bool>("debug"), Some(Ok(true)));
+    /// assert!(matches!(params.get_parsed::<u32>("size"), Some(Err(_))));
+    /// assert_eq!(params.get_parsed::<u32>("limit"), None);
+    /// ```
+    pub fn get_parsed<T: core::str::FromStr>(&self, key: &str) -> Option<Result<T, T::Err>> {
+        self.get(key).map(str::parse)
+    }
+
+    /// Parses the value of the key, or returns `default` if the key is missing or its value
+    /// cannot be parsed.
+    ///
+    /// ```
+    /// use ada_url::UrlSearchParams;
+    /// let params = UrlSearchParams::parse("page=2&size=big")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// assert_eq!(params.get_or("page", 1), 2);
+    /// assert_eq!(params.get_or("size", 10), 10);
+    /// assert_eq!(params.get_or("limit", 50), 50);
+    /// ```
+    pub fn get_or<T: core::str::FromStr>(&self, key: &str, default: T) -> T {
+        self.get_parsed(key).and_then(Result::ok).unwrap_or(default)
+    }
+
     /// Returns all values of the key.
     ///
     /// ```
@@ -182,14 +409,65 @@ impl UrlSearchParams {
     /// let pairs = params.get_all("a");
     /// assert_eq!(pairs.len(), 2);
     /// ```
-    pub fn get_all(&self, key: &str) -> UrlSearchParamsEntry {
-        unsafe {
-            let strings = ffi::ada_search_params_get_all(self.0, key.as_ptr().cast(), key.len());
-            let size = ffi::ada_strings_size(strings);
-            UrlSearchParamsEntry::new(strings, size)
+    pub fn get_all(&self, key: &str) -> UrlSearchParamsEntry<'_> {
+        let mut keys = self.keys();
+        // Borrow the key from the params, so that the entry does not depend on `key`.
+        match keys.find(|candidate| *candidate == key) {
+            Some(key) => UrlSearchParamsEntry {
+                params: self,
+                key,
+                size: 1 + keys.filter(|candidate| *candidate == key).count(),
+            },
+            None => UrlSearchParamsEntry {
+                params: self,
+                key: "",
+                size: 0,
+            },
         }
     }
 
+    /// Returns the key/value pair at `index`, in order of insertion.
+    ///
+    /// ```
+    /// use ada_url::UrlSearchParams;
+    /// let params = UrlSearchParams::parse("a=1&b=2&a=3")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// assert_eq!(params.get_index(1), Some(("b", "2")));
+    /// assert_eq!(params.get_index(3), None);
+    /// ```
+    pub fn get_index(&self, index: usize) -> Option<(&str, &str)> {
+        (index < self.len()).then(|| Pairs::new(self).get(index))
+    }
+
+    /// Returns the first key/value pair.
+    ///
+    /// ```
+    /// use ada_url::UrlSearchParams;
+    /// let params = UrlSearchParams::parse("a=1&b=2")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// assert_eq!(params.first(), Some(("a", "1")));
+    /// ```
+    pub fn first(&self) -> Option<(&str, &str)> {
+        self.get_index(0)
+    }
+
+    /// Returns the last key/value pair.
+    ///
+    /// ```
+    /// use ada_url::UrlSearchParams;
+    /// let params = UrlSearchParams::parse("a=1&b=2")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// assert_eq!(params.last(), Some(("b", "2")));
+    /// let empty = UrlSearchParams::parse("")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// assert_eq!(empty.last(), None);
+    /// ```
+    pub fn last(&self) -> Option<(&str, &str)> {
+        self.len()
+            .checked_sub(1)
+            .and_then(|index| self.get_index(index))
+    }
+
     /// Returns all keys as an iterator
     ///
     /// ```
@@ -198,9 +476,10 @@ impl UrlSearchParams {
     ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
     /// let mut keys = params.keys();
     /// assert!(keys.next().is_some());
-    pub fn keys(&self) -> UrlSearchParamsKeyIterator {
-        let iterator = unsafe { ffi::ada_search_params_get_keys(self.0) };
-        UrlSearchParamsKeyIterator::new(iterator)
+    pub fn keys(&self) -> UrlSearchParamsKeyIterator<'_> {
+        UrlSearchParamsKeyIterator {
+            pairs: Pairs::new(self),
+        }
     }
 
     /// Returns all values as an iterator
@@ -211,29 +490,187 @@ impl UrlSearchParams {
     ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
     /// let mut values = params.values();
     /// assert!(values.next().is_some());
-    pub fn values(&self) -> UrlSearchParamsValueIterator {
-        let iterator = unsafe { ffi::ada_search_params_get_values(self.0) };
-        UrlSearchParamsValueIterator::new(iterator)
+    /// ```
+    ///
+    /// All the iterators of UrlSearchParams can be reversed, e.g. to find the last value:
+    ///
+    /// ```
+    /// use ada_url::UrlSearchParams;
+    /// let params = UrlSearchParams::parse("a=1&b=2&a=3")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// let last_a = params.entries().rev().find(|(key, _)| *key == "a");
+    /// assert_eq!(last_a, Some(("a", "3")));
+    /// let mut values = params.values();
+    /// assert_eq!(values.next_back(), Some("3"));
+    /// assert_eq!(values.next(), Some("1"));
+    /// assert_eq!(values.next_back(), Some("2"));
+    /// assert_eq!(values.next(), None);
+    /// assert_eq!(values.next_back(), None);
+    pub fn values(&self) -> UrlSearchParamsValueIterator<'_> {
+        UrlSearchParamsValueIterator {
+            pairs: Pairs::new(self),
+        }
     }
 
     /// Returns all entries as an iterator
     ///
     /// ```
     /// use ada_url::UrlSearchParams;
-    /// let params = UrlSearchParams::parse("a=1")
+    /// let params = UrlSearchParams::parse("a=1&a=2&b=3")
     ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
     /// let mut entries = params.entries();
+    /// assert_eq!(entries.len(), 3);
     /// assert_eq!(entries.next(), Some(("a", "1")));
+    /// assert_eq!(entries.len(), 2);
+    /// assert_eq!(entries.by_ref().count(), 2);
+    /// assert_eq!(entries.next(), None);
+    /// assert_eq!(params.keys().size_hint(), (3, Some(3)));
+    /// ```
+    pub fn entries(&self) -> UrlSearchParamsEntryIterator<'_> {
+        UrlSearchParamsEntryIterator {
+            pairs: Pairs::new(self),
+        }
+    }
+
+    /// Consumes the UrlSearchParams struct and returns its entries as owned pairs, which can
+    /// outlive it.
+    ///
+    /// This method is only available if the `alloc` Cargo feature is enabled.
+    ///
     /// ```
-    pub fn entries(&self) -> UrlSearchParamsEntryIterator {
-        let iterator = unsafe { ffi::ada_search_params_get_entries(self.0) };
-        UrlSearchParamsEntryIterator::new(iterator)
+    /// use ada_url::UrlSearchParams;
+    /// let params = UrlSearchParams::parse("a=1&b=x+y")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// let pairs: Vec<(String, String)> = params.into_pairs().collect();
+    /// assert_eq!(pairs, [("a".into(), "1".into()), ("b".into(), "x y".into())]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn into_pairs(
+        self,
+    ) -> alloc::vec::IntoIter<(alloc::string::String, alloc::string::String)> {
+        self.entries()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect::<alloc::vec::Vec<_>>()
+            .into_iter()
     }
 }
 
-#[cfg(feature = "std")]
+impl UrlSearchParams {
+    /// Groups the values by key, in order of insertion.
+    ///
+    /// This method is only available if the `std` Cargo feature is enabled.
+    ///
+    /// ```
+    /// use ada_url::UrlSearchParams;
+    /// let params = UrlSearchParams::parse("a=1&b=2&a=3")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// let map = params.to_hashmap();
+    /// assert_eq!(map["a"], ["1", "3"]);
+    /// assert_eq!(map["b"], ["2"]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_hashmap(&self) -> std::collections::HashMap<String, Vec<String>> {
+        let mut map = std::collections::HashMap::<String, Vec<String>>::new();
+        for (key, value) in self {
+            map.entry(key.into()).or_default().push(value.into());
+        }
+        map
+    }
+
+    /// Groups the values by key, in order of insertion, with the keys sorted.
+    ///
+    /// This method is only available if the `alloc` Cargo feature is enabled.
+    ///
+    /// ```
+    /// use ada_url::UrlSearchParams;
+    /// let params = UrlSearchParams::parse("b=2&a=1&a=3")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// let map = params.to_btreemap();
+    /// assert_eq!(map.keys().collect::<Vec<_>>(), ["a", "b"]);
+    /// assert_eq!(map["a"], ["1", "3"]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_btreemap(
+        &self,
+    ) -> alloc::collections::BTreeMap<alloc::string::String, alloc::vec::Vec<alloc::string::String>>
+    {
+        let mut map = alloc::collections::BTreeMap::<_, alloc::vec::Vec<_>>::new();
+        for (key, value) in self {
+            map.entry(key.into()).or_default().push(value.into());
+        }
+        map
+    }
+
+    /// Maps each key to a single value, chosen by `policy` when the key is repeated.
+    ///
+    /// This method is only available if the `std` Cargo feature is enabled.
+    ///
+    /// ```
+    /// use ada_url::{DuplicatePolicy, UrlSearchParams};
+    /// let params = UrlSearchParams::parse("a=1&b=2&a=3")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// let first = params.to_single_value_map(DuplicatePolicy::FirstWins);
+    /// assert_eq!(first["a"], "1");
+    /// let last = params.to_single_value_map(DuplicatePolicy::LastWins);
+    /// assert_eq!(last["a"], "3");
+    /// assert_eq!(last["b"], "2");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_single_value_map(
+        &self,
+        policy: DuplicatePolicy,
+    ) -> std::collections::HashMap<String, String> {
+        let mut map = std::collections::HashMap::new();
+        for (key, value) in self {
+            match policy {
+                DuplicatePolicy::FirstWins => {
+                    map.entry(key.into()).or_insert_with(|| value.into());
+                }
+                DuplicatePolicy::LastWins => {
+                    map.insert(key.into(), value.into());
+                }
+            }
+        }
+        map
+    }
+}
+
+/// Iterates over the entries, like [`UrlSearchParams::entries`].
+///
+/// ```
+/// use ada_url::UrlSearchParams;
+/// let params = UrlSearchParams::parse("a=1&b=2")
+///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+/// let mut keys = String::new();
+/// for (key, value) in &params {
+///     keys.push_str(key);
+///     assert_eq!(params.get(key), Some(value));
+/// }
+/// assert_eq!(keys, "ab");
+/// ```
+impl<'a> IntoIterator for &'a UrlSearchParams {
+    type Item = (&'a str, &'a str);
+    type IntoIter = UrlSearchParamsEntryIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries()
+    }
+}
+
+/// Parses search params, e.g. the query of a URL.
+///
+/// This implementation only requires the `alloc` Cargo feature.
+///
+/// ```
+/// use ada_url::UrlSearchParams;
+///
+/// let params: UrlSearchParams = "?a=1&b=2".parse().unwrap();
+/// assert_eq!(params.get("a"), Some("1"));
+/// assert_eq!(params.len(), 2);
+/// ```
+#[cfg(feature = "alloc")]
 impl core::str::FromStr for UrlSearchParams {
-    type Err = ParseUrlError<Box<str>>;
+    type Err = ParseUrlError<alloc::boxed::Box<str>>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Self::parse(s).map_err(|ParseUrlError { input }| ParseUrlError {
@@ -256,6 +693,20 @@ impl core::fmt::Display for UrlSearchParams {
     }
 }
 
+/// Formats the decoded key/value pairs as a list of tuples.
+///
+/// ```
+/// use ada_url::UrlSearchParams;
+/// let params = UrlSearchParams::parse("a=1&b=x+y")
+///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+/// assert_eq!(format!("{params:?}"), r#"[("a", "1"), ("b", "x y")]"#);
+/// ```
+impl core::fmt::Debug for UrlSearchParams {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.entries()).finish()
+    }
+}
+
 #[cfg(feature = "std")]
 impl<Input> Extend<(Input, Input)> for UrlSearchParams
 where
@@ -272,9 +723,7 @@ where
     /// assert_eq!(params.len(), 3);
     /// ```
     fn extend<T: IntoIterator<Item = (Input, Input)>>(&mut self, iter: T) {
-        for item in iter {
-            self.append(item.0.as_ref(), item.1.as_ref());
-        }
+        self.append_pairs(iter);
     }
 }
 
@@ -292,102 +741,184 @@ where
     /// assert_eq!(params.len(), 5);
     /// ```
     fn from_iter<T: IntoIterator<Item = (Input, Input)>>(iter: T) -> Self {
+        Self::from_pairs(iter)
+    }
+}
+
+impl UrlSearchParams {
+    fn from_pairs<K, V>(pairs: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
         let mut params = UrlSearchParams::parse("")
             .expect("Should be able to parse empty string. This is likely due to a bug");
-        for item in iter {
-            params.append(item.0.as_ref(), item.1.as_ref());
+        for (key, value) in pairs {
+            params.append(key.as_ref(), value.as_ref());
         }
         params
     }
 }
 
-#[derive(Hash)]
-pub struct UrlSearchParamsKeyIterator<'a> {
-    iterator: *mut ffi::ada_url_search_params_keys_iter,
-    _phantom: core::marker::PhantomData<&'a str>,
+/// Appends the pairs of a slice, in order.
+///
+/// ```
+/// use ada_url::UrlSearchParams;
+/// let pairs: &[(&str, String)] = &[("a", "1".to_owned()), ("b", "x y".to_owned())];
+/// let params = UrlSearchParams::from(pairs);
+/// assert_eq!(params.to_string(), "a=1&b=x+y");
+/// ```
+impl<K, V> From<&[(K, V)]> for UrlSearchParams
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    fn from(pairs: &[(K, V)]) -> Self {
+        Self::from_pairs(pairs.iter().map(|(key, value)| (key, value)))
+    }
 }
 
-impl Drop for UrlSearchParamsKeyIterator<'_> {
-    fn drop(&mut self) {
-        unsafe { ffi::ada_free_search_params_keys_iter(self.iterator) }
+/// Appends the pairs of an array, in order.
+///
+/// ```
+/// use ada_url::UrlSearchParams;
+/// let params = UrlSearchParams::from([("a", "1"), ("b", "2")]);
+/// assert_eq!(params.to_string(), "a=1&b=2");
+/// ```
+impl<K, V, const N: usize> From<[(K, V); N]> for UrlSearchParams
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    fn from(pairs: [(K, V); N]) -> Self {
+        Self::from_pairs(pairs)
     }
 }
 
-impl<'a> Iterator for UrlSearchParamsKeyIterator<'a> {
-    type Item = &'a str;
+/// Appends the entries of a map, in the iteration order of the map, which is unspecified;
+/// see `From<BTreeMap>` for a deterministic order.
+///
+/// This implementation is only available if the `std` Cargo feature is enabled.
+///
+/// ```
+/// use ada_url::UrlSearchParams;
+/// use std::collections::HashMap;
+/// let params = UrlSearchParams::from(HashMap::from([("a", "1")]));
+/// assert_eq!(params.to_string(), "a=1");
+/// ```
+#[cfg(feature = "std")]
+impl<K, V, S> From<std::collections::HashMap<K, V, S>> for UrlSearchParams
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    fn from(map: std::collections::HashMap<K, V, S>) -> Self {
+        Self::from_pairs(map)
+    }
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let has_next = unsafe { ffi::ada_search_params_keys_iter_has_next(self.iterator) };
-        if has_next {
-            let string = unsafe { ffi::ada_search_params_keys_iter_next(self.iterator) };
-            Some(string.as_str())
-        } else {
-            None
-        }
+/// Appends the entries of a map, sorted by key.
+///
+/// This implementation is only available if the `alloc` Cargo feature is enabled.
+///
+/// ```
+/// use ada_url::UrlSearchParams;
+/// use std::collections::BTreeMap;
+/// let params = UrlSearchParams::from(BTreeMap::from([("b", "2"), ("a", "1")]));
+/// assert_eq!(params.to_string(), "a=1&b=2");
+/// ```
+#[cfg(feature = "alloc")]
+impl<K, V> From<alloc::collections::BTreeMap<K, V>> for UrlSearchParams
+where
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    fn from(map: alloc::collections::BTreeMap<K, V>) -> Self {
+        Self::from_pairs(map)
     }
 }
 
 #[derive(Hash)]
-pub struct UrlSearchParamsValueIterator<'a> {
-    iterator: *mut ffi::ada_url_search_params_values_iter,
-    _phantom: core::marker::PhantomData<&'a str>,
+pub struct UrlSearchParamsKeyIterator<'a> {
+    pairs: Pairs<'a>,
 }
 
-impl<'a> UrlSearchParamsKeyIterator<'a> {
-    fn new(iterator: *mut ffi::ada_url_search_params_keys_iter) -> UrlSearchParamsKeyIterator<'a> {
-        UrlSearchParamsKeyIterator {
-            iterator,
-            _phantom: core::marker::PhantomData,
-        }
+impl<'a> Iterator for UrlSearchParamsKeyIterator<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pairs.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.pairs.len();
+        (remaining, Some(remaining))
     }
 }
 
-impl Drop for UrlSearchParamsValueIterator<'_> {
-    fn drop(&mut self) {
-        unsafe { ffi::ada_free_search_params_values_iter(self.iterator) }
+impl DoubleEndedIterator for UrlSearchParamsKeyIterator<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.pairs.next_back().map(|(key, _)| key)
     }
 }
 
+impl ExactSizeIterator for UrlSearchParamsKeyIterator<'_> {}
+
+impl core::iter::FusedIterator for UrlSearchParamsKeyIterator<'_> {}
+
+#[derive(Hash)]
+pub struct UrlSearchParamsValueIterator<'a> {
+    pairs: Pairs<'a>,
+}
+
 impl<'a> Iterator for UrlSearchParamsValueIterator<'a> {
     type Item = &'a str;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let has_next = unsafe { ffi::ada_search_params_values_iter_has_next(self.iterator) };
-        if has_next {
-            let string = unsafe { ffi::ada_search_params_values_iter_next(self.iterator) };
-            Some(string.as_str())
-        } else {
-            None
-        }
+        self.pairs.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.pairs.len();
+        (remaining, Some(remaining))
     }
 }
 
-impl<'a> UrlSearchParamsValueIterator<'a> {
-    fn new(
-        iterator: *mut ffi::ada_url_search_params_values_iter,
-    ) -> UrlSearchParamsValueIterator<'a> {
-        UrlSearchParamsValueIterator {
-            iterator,
-            _phantom: core::marker::PhantomData,
-        }
+impl DoubleEndedIterator for UrlSearchParamsValueIterator<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.pairs.next_back().map(|(_, value)| value)
     }
 }
 
+impl ExactSizeIterator for UrlSearchParamsValueIterator<'_> {}
+
+impl core::iter::FusedIterator for UrlSearchParamsValueIterator<'_> {}
+
+/// The values of a key in a [`UrlSearchParams`], returned by [`UrlSearchParams::get_all`].
+///
+/// The values are borrowed from the params rather than copied, and can be iterated over or
+/// indexed:
+///
+/// ```
+/// use ada_url::UrlSearchParams;
+/// let params = UrlSearchParams::parse("a=1&b=2&a=3")
+///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+/// let mut sum = 0;
+/// for value in params.get_all("a") {
+///     sum += value.parse::<i32>().unwrap();
+/// }
+/// assert_eq!(sum, 4);
+/// assert_eq!(&params.get_all("a")[1], "3");
+/// ```
+#[derive(Hash)]
 pub struct UrlSearchParamsEntry<'a> {
-    strings: *mut ffi::ada_strings,
+    params: &'a UrlSearchParams,
+    /// The key, borrowed from the first matching pair.
+    key: &'a str,
     size: usize,
-    _phantom: core::marker::PhantomData<&'a str>,
 }
 
 impl<'a> UrlSearchParamsEntry<'a> {
-    fn new(strings: *mut ffi::ada_strings, size: usize) -> UrlSearchParamsEntry<'a> {
-        UrlSearchParamsEntry {
-            strings,
-            size,
-            _phantom: core::marker::PhantomData,
-        }
-    }
-
     /// Returns whether the key value pair is empty or not
     ///
     /// ```
@@ -427,72 +958,303 @@ impl<'a> UrlSearchParamsEntry<'a> {
     /// assert_eq!(pairs.get(2), None);
     /// assert_eq!(pairs.get(55), None);
     /// ```
-    pub fn get(&self, index: usize) -> Option<&str> {
-        if self.size == 0 || index > self.size - 1 {
-            return None;
+    pub fn get(&self, index: usize) -> Option<&'a str> {
+        self.iter().nth(index)
+    }
+
+    /// Returns an iterator over the values.
+    ///
+    /// ```
+    /// use ada_url::UrlSearchParams;
+    /// let params = UrlSearchParams::parse("a=1&b=2&a=3")
+    ///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+    /// let pairs = params.get_all("a");
+    /// assert_eq!(pairs.iter().rev().collect::<Vec<_>>(), ["3", "1"]);
+    /// ```
+    pub fn iter(&self) -> UrlSearchParamsEntryValues<'a> {
+        UrlSearchParamsEntryValues {
+            pairs: Pairs::new(self.params),
+            key: self.key,
+            remaining: self.size,
         }
+    }
+}
 
-        unsafe {
-            let string = ffi::ada_strings_get(self.strings, index);
-            Some(string.as_str())
+/// Returns the value at `index`.
+///
+/// # Panics
+///
+/// Panics if `index` is out of bounds.
+impl core::ops::Index<usize> for UrlSearchParamsEntry<'_> {
+    type Output = str;
+
+    fn index(&self, index: usize) -> &str {
+        match self.get(index) {
+            Some(value) => value,
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {index}",
+                self.size
+            ),
         }
     }
 }
 
-impl Drop for UrlSearchParamsEntry<'_> {
-    fn drop(&mut self) {
-        unsafe { ffi::ada_free_strings(self.strings) }
+impl<'a> IntoIterator for UrlSearchParamsEntry<'a> {
+    type Item = &'a str;
+    type IntoIter = UrlSearchParamsEntryValues<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
+impl<'a> IntoIterator for &UrlSearchParamsEntry<'a> {
+    type Item = &'a str;
+    type IntoIter = UrlSearchParamsEntryValues<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Collects the values, which stay borrowed from the [`UrlSearchParams`].
+///
+/// ```
+/// use ada_url::UrlSearchParams;
+/// let params = UrlSearchParams::parse("a=1&b=2&a=3")
+///     .expect("String should have been able to be parsed into an UrlSearchParams.");
+/// let values: Vec<&str> = params.get_all("a").into();
+/// assert_eq!(values, ["1", "3"]);
+/// ```
 #[cfg(feature = "std")]
 impl<'a> From<UrlSearchParamsEntry<'a>> for Vec<&'a str> {
     fn from(val: UrlSearchParamsEntry<'a>) -> Self {
-        let mut vec = Vec::with_capacity(val.size);
-        unsafe {
-            for index in 0..val.size {
-                let string = ffi::ada_strings_get(val.strings, index);
-                let slice = core::slice::from_raw_parts(string.data.cast(), string.length);
-                vec.push(core::str::from_utf8_unchecked(slice));
-            }
-        }
-        vec
+        val.into_iter().collect()
     }
 }
 
+/// An iterator over the values of a [`UrlSearchParamsEntry`].
 #[derive(Hash)]
-pub struct UrlSearchParamsEntryIterator<'a> {
-    iterator: *mut ffi::ada_url_search_params_entries_iter,
-    _phantom: core::marker::PhantomData<&'a str>,
+pub struct UrlSearchParamsEntryValues<'a> {
+    pairs: Pairs<'a>,
+    key: &'a str,
+    remaining: usize,
 }
 
-impl<'a> UrlSearchParamsEntryIterator<'a> {
-    fn new(
-        iterator: *mut ffi::ada_url_search_params_entries_iter,
-    ) -> UrlSearchParamsEntryIterator<'a> {
-        UrlSearchParamsEntryIterator {
-            iterator,
-            _phantom: core::marker::PhantomData,
+impl<'a> Iterator for UrlSearchParamsEntryValues<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
         }
+        let (_, value) =
+            core::iter::from_fn(|| self.pairs.next()).find(|(key, _)| *key == self.key)?;
+        self.remaining -= 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
 }
 
-impl Drop for UrlSearchParamsEntryIterator<'_> {
-    fn drop(&mut self) {
-        unsafe { ffi::ada_free_search_params_entries_iter(self.iterator) }
+impl DoubleEndedIterator for UrlSearchParamsEntryValues<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let (_, value) =
+            core::iter::from_fn(|| self.pairs.next_back()).find(|(key, _)| *key == self.key)?;
+        self.remaining -= 1;
+        Some(value)
     }
 }
 
+impl ExactSizeIterator for UrlSearchParamsEntryValues<'_> {}
+
+impl core::iter::FusedIterator for UrlSearchParamsEntryValues<'_> {}
+
+/// An iterator over the key/value pairs of a [`UrlSearchParams`], returned by
+/// [`UrlSearchParams::entries`].
+///
+/// The iterators of UrlSearchParams borrow it, so that it cannot be modified or dropped while
+/// the strings they return are in use:
+///
+/// ```compile_fail
+/// use ada_url::UrlSearchParams;
+/// let mut params = UrlSearchParams::parse("a=1").unwrap();
+/// for (key, _) in params.entries() {
+///     params.append(key, "2");
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use ada_url::UrlSearchParams;
+/// let params = UrlSearchParams::parse("a=1").unwrap();
+/// let mut keys = params.keys();
+/// drop(params);
+/// keys.next();
+/// ```
+#[derive(Hash)]
+pub struct UrlSearchParamsEntryIterator<'a> {
+    pairs: Pairs<'a>,
+}
+
 impl<'a> Iterator for UrlSearchParamsEntryIterator<'a> {
     type Item = (&'a str, &'a str);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let has_next = unsafe { ffi::ada_search_params_entries_iter_has_next(self.iterator) };
-        if has_next {
-            let pair = unsafe { ffi::ada_search_params_entries_iter_next(self.iterator) };
-            Some((pair.key.as_str(), pair.value.as_str()))
-        } else {
-            None
+        self.pairs.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.pairs.len();
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for UrlSearchParamsEntryIterator<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.pairs.next_back()
+    }
+}
+
+impl ExactSizeIterator for UrlSearchParamsEntryIterator<'_> {}
+
+impl core::iter::FusedIterator for UrlSearchParamsEntryIterator<'_> {}
+
+/// A guard over the query pairs of a [`Url`], returned by [`Url::query_pairs_mut`], which
+/// writes the query back into the URL when it is dropped, if the pairs were modified.
+///
+/// The guard dereferences to [`UrlSearchParams`], so every method of the params is available.
+///
+/// ```
+/// use ada_url::Url;
+/// let mut url = Url::parse("https://example.com/?a=1", None).expect("Invalid URL");
+/// url.query_pairs_mut()
+///     .append_pair("b", "x y")
+///     .extend_pairs([("c", "3")]);
+/// assert_eq!(url.href(), "https://example.com/?a=1&b=x+y&c=3");
+///
+/// url.query_pairs_mut().clear();
+/// assert_eq!(url.href(), "https://example.com/");
+/// ```
+pub struct QueryPairsMut<'a> {
+    url: &'a mut Url,
+    params: UrlSearchParams,
+    modified: bool,
+}
+
+impl<'a> QueryPairsMut<'a> {
+    pub(crate) fn new(url: &'a mut Url) -> Self {
+        let params = UrlSearchParams::from_url(url);
+        Self {
+            url,
+            params,
+            modified: false,
+        }
+    }
+
+    /// Appends a key/value pair.
+    pub fn append_pair(&mut self, key: &str, value: &str) -> &mut Self {
+        self.append(key, value);
+        self
+    }
+
+    /// Appends the key/value pairs, in order, with [`UrlSearchParams::append_pairs`].
+    ///
+    /// This method is only available if the `alloc` Cargo feature is enabled.
+    #[cfg(feature = "alloc")]
+    pub fn extend_pairs<K, V, I>(&mut self, pairs: I) -> &mut Self
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        self.append_pairs(pairs);
+        self
+    }
+
+    /// Removes all the pairs, which removes the query from the URL.
+    pub fn clear(&mut self) -> &mut Self {
+        self.params.clear();
+        self.modified = true;
+        self
+    }
+}
+
+impl core::ops::Deref for QueryPairsMut<'_> {
+    type Target = UrlSearchParams;
+
+    fn deref(&self) -> &Self::Target {
+        &self.params
+    }
+}
+
+impl core::ops::DerefMut for QueryPairsMut<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.modified = true;
+        &mut self.params
+    }
+}
+
+impl core::fmt::Debug for QueryPairsMut<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.params.fmt(f)
+    }
+}
+
+impl Drop for QueryPairsMut<'_> {
+    fn drop(&mut self) {
+        if self.modified {
+            self.url.set_search_params(&self.params);
+        }
+    }
+}
+
+/// A double-ended cursor over the pairs of a [`UrlSearchParams`], by index.
+///
+/// The cursor borrows the params, so they cannot be modified or dropped while the strings
+/// it returns, which point into them, are alive.
+#[derive(Hash)]
+struct Pairs<'a> {
+    params: &'a UrlSearchParams,
+    /// The index of the next pair from the front.
+    front: usize,
+    /// One past the index of the next pair from the back.
+    back: usize,
+}
+
+impl<'a> Pairs<'a> {
+    fn new(params: &'a UrlSearchParams) -> Self {
+        Self {
+            params,
+            front: 0,
+            back: params.len(),
         }
     }
+
+    fn len(&self) -> usize {
+        self.back - self.front
+    }
+
+    fn get(&self, index: usize) -> (&'a str, &'a str) {
+        let pair = unsafe { ffi::ada_search_params_get_pair(self.params.0, index) };
+        (pair.key.as_str(), pair.value.as_str())
+    }
+
+    fn next(&mut self) -> Option<(&'a str, &'a str)> {
+        (self.front < self.back).then(|| {
+            self.front += 1;
+            self.get(self.front - 1)
+        })
+    }
+
+    fn next_back(&mut self) -> Option<(&'a str, &'a str)> {
+        (self.front < self.back).then(|| {
+            self.back -= 1;
+            self.get(self.back)
+        })
+    }
 }
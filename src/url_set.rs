@@ -0,0 +1,166 @@
+use crate::{NormalizeOptions, Url};
+use std::collections::{hash_set, HashSet};
+
+/// A set of URLs which are normalized with [`Url::normalized`] before being stored, so that
+/// equivalent URLs are only stored once, e.g. in the frontier of a crawler.
+///
+/// URLs are hashed by their href, so membership checks take constant time regardless of the
+/// size of the set.
+///
+/// This type is only available if the `std` Cargo feature is enabled.
+///
+/// ```
+/// use ada_url::{NormalizeOptions, Url, UrlSet};
+///
+/// let parse = |input| Url::parse(input, None).expect("Invalid URL");
+/// let mut set = UrlSet::new(NormalizeOptions::all());
+/// assert!(set.insert(parse("https://example.com/a/?b=1&a=2")));
+/// assert!(!set.insert(parse("https://EXAMPLE.com/a?a=2&b=1#top")));
+/// assert!(set.contains_str("https://example.com:443/a/?a=2&b=1"));
+/// assert_eq!(set.len(), 1);
+/// assert_eq!(set.iter().next().unwrap().href(), "https://example.com/a?a=2&b=1");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UrlSet {
+    urls: HashSet<Url>,
+    options: NormalizeOptions,
+}
+
+impl UrlSet {
+    /// Creates an empty set which normalizes URLs with the given options.
+    #[must_use]
+    pub fn new(options: NormalizeOptions) -> Self {
+        Self {
+            urls: HashSet::new(),
+            options,
+        }
+    }
+
+    /// Creates an empty set with space for at least `capacity` URLs.
+    #[must_use]
+    pub fn with_capacity(capacity: usize, options: NormalizeOptions) -> Self {
+        Self {
+            urls: HashSet::with_capacity(capacity),
+            options,
+        }
+    }
+
+    /// Returns the normalization options of the set.
+    #[must_use]
+    pub fn options(&self) -> NormalizeOptions {
+        self.options
+    }
+
+    /// Returns the number of URLs in the set.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.urls.len()
+    }
+
+    /// Returns true if the set contains no URL.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.urls.is_empty()
+    }
+
+    /// Adds the normalized form of `url` to the set, returning true if it was not present.
+    pub fn insert(&mut self, url: Url) -> bool {
+        if self.options == NormalizeOptions::default() {
+            return self.urls.insert(url);
+        }
+        self.urls.insert(url.normalized(self.options))
+    }
+
+    /// Returns true if the set contains the normalized form of `url`.
+    #[must_use]
+    pub fn contains(&self, url: &Url) -> bool {
+        self.urls.contains(url.normalized(self.options).href())
+    }
+
+    /// Parses `input` and returns true if the set contains its normalized form, or false if it
+    /// is not a valid URL.
+    #[must_use]
+    pub fn contains_str(&self, input: &str) -> bool {
+        Url::parse(input, None).is_ok_and(|url| self.contains(&url))
+    }
+
+    /// Removes the normalized form of `url` from the set, returning true if it was present.
+    pub fn remove(&mut self, url: &Url) -> bool {
+        self.urls.remove(url.normalized(self.options).href())
+    }
+
+    /// Removes all the URLs of the set, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.urls.clear();
+    }
+
+    /// Returns an iterator over the normalized URLs of the set, in arbitrary order.
+    pub fn iter(&self) -> hash_set::Iter<'_, Url> {
+        self.urls.iter()
+    }
+}
+
+impl Extend<Url> for UrlSet {
+    fn extend<T: IntoIterator<Item = Url>>(&mut self, iter: T) {
+        for url in iter {
+            self.insert(url);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a UrlSet {
+    type Item = &'a Url;
+    type IntoIter = hash_set::Iter<'a, Url>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for UrlSet {
+    type Item = Url;
+    type IntoIter = hash_set::IntoIter<Url>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.urls.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NormalizeOptions, Url, UrlSet};
+
+    #[test]
+    fn should_deduplicate_equivalent_urls() {
+        let options = NormalizeOptions {
+            remove_fragment: true,
+            ..NormalizeOptions::default()
+        };
+        let mut set = UrlSet::new(options);
+        set.extend(
+            [
+                "https://a.com/#1",
+                "https://a.com/#2",
+                "https://a.com/?",
+                "https://a.com/x/",
+            ]
+            .map(|input| Url::parse(input, None).unwrap()),
+        );
+        assert_eq!(set.len(), 3);
+        assert!(set.contains_str("https://a.com/#3"));
+        assert!(!set.contains_str("https://a.com/x"));
+        assert!(!set.contains_str("not a url"));
+        assert!(set.remove(&Url::parse("https://a.com/#4", None).unwrap()));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn should_keep_urls_unchanged_by_default() {
+        let mut set = UrlSet::default();
+        assert!(set.insert(Url::parse("https://a.com/#1", None).unwrap()));
+        assert!(set.insert(Url::parse("https://a.com/#2", None).unwrap()));
+        assert_eq!(set.len(), 2);
+        set.clear();
+        assert!(set.is_empty());
+    }
+}
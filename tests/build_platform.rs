@@ -0,0 +1,89 @@
+//! Tests of the C++ standard library selection of `build.rs`, which Cargo does not test.
+
+#[path = "../build/platform.rs"]
+mod platform;
+
+use platform::{linker_plugin_lto, CppStdlib};
+
+#[test]
+fn should_select_the_system_library_of_bsd_and_illumos() {
+    let cases = [
+        ("freebsd", CppStdlib::LibCpp),
+        ("openbsd", CppStdlib::LibCpp),
+        ("netbsd", CppStdlib::LibStdCpp),
+        ("dragonfly", CppStdlib::LibStdCpp),
+        ("illumos", CppStdlib::LibStdCpp),
+        ("solaris", CppStdlib::LibStdCpp),
+    ];
+    for (os, expected) in cases {
+        assert_eq!(
+            CppStdlib::for_target(os, "", "unknown", false, false),
+            expected,
+            "{os}"
+        );
+    }
+}
+
+#[test]
+fn should_select_the_library_of_the_compiler() {
+    // `g++` from the ports of FreeBSD and OpenBSD uses its own libstdc++.
+    let gcc = CppStdlib::for_target("freebsd", "", "unknown", true, false);
+    assert_eq!(gcc.link_name(), Some("stdc++"));
+    let gcc = CppStdlib::for_target("openbsd", "", "unknown", true, true);
+    assert_eq!(gcc.link_name(), Some("stdc++"));
+    // Clang can use libc++ instead of the libstdc++ of GCC with the `libcpp` feature.
+    let clang = CppStdlib::for_target("netbsd", "", "unknown", false, true);
+    assert_eq!(clang.link_name(), Some("c++"));
+    let clang = CppStdlib::for_target("illumos", "", "unknown", false, true);
+    assert_eq!(clang.link_name(), Some("c++"));
+}
+
+#[test]
+fn should_select_the_library_of_other_platforms() {
+    let cases = [
+        ("linux", "gnu", "unknown", CppStdlib::LibStdCpp),
+        ("macos", "", "apple", CppStdlib::LibCpp),
+        ("ios", "", "apple", CppStdlib::LibCpp),
+        ("android", "", "unknown", CppStdlib::LibCpp),
+        ("windows", "gnu", "pc", CppStdlib::LibStdCpp),
+        ("windows", "msvc", "pc", CppStdlib::Msvc),
+    ];
+    for (os, env, vendor, expected) in cases {
+        assert_eq!(
+            CppStdlib::for_target(os, env, vendor, false, false),
+            expected,
+            "{os}-{env}"
+        );
+    }
+    assert_eq!(CppStdlib::Msvc.link_name(), None);
+}
+
+#[test]
+fn should_detect_linker_plugin_lto() {
+    let enabled = [
+        "-Clinker-plugin-lto",
+        "-Clinker-plugin-lto=yes",
+        "-Clinker-plugin-lto=/usr/lib/LLVMgold.so",
+        "-C\x1flinker-plugin-lto",
+        "--codegen\x1flinker-plugin-lto=on",
+        "--codegen=linker-plugin-lto",
+        "-Copt-level=3\x1f-Clinker-plugin-lto\x1f-Clinker=clang",
+        "-Clinker-plugin-lto=no\x1f-Clinker-plugin-lto",
+    ];
+    for rustflags in enabled {
+        assert!(linker_plugin_lto(rustflags), "{rustflags:?}");
+    }
+    let disabled = [
+        "",
+        "-Clinker-plugin-lto=no",
+        "-Clinker-plugin-lto=off",
+        "-Clinker-plugin-lto\x1f-Clinker-plugin-lto=n",
+        "-Clinker=clang",
+        "-Clto=thin",
+        "linker-plugin-lto",
+        "--cfg\x1flinker-plugin-lto",
+    ];
+    for rustflags in disabled {
+        assert!(!linker_plugin_lto(rustflags), "{rustflags:?}");
+    }
+}
@@ -0,0 +1,93 @@
+//! Runs the Web Platform Tests URL parsing cases through the Rust bindings.
+//!
+//! `data/urltestdata.json` is vendored from
+//! <https://github.com/web-platform-tests/wpt/blob/master/url/resources/urltestdata.json>; its
+//! first entry records the revision it was pulled from. Every case is parsed with
+//! [`Url::parse`] and each component getter is compared with the expected value, so that a
+//! binding bug in a single getter is reported on its own.
+
+#![cfg(feature = "std")]
+
+use ada_url::{Url, UrlSearchParams};
+use serde_json::Value;
+
+const URL_TEST_DATA: &str = include_str!("data/urltestdata.json");
+
+type Getter = fn(&Url) -> String;
+
+/// Getters compared against the fields of the same name.
+const COMPONENTS: &[(&str, Getter)] = &[
+    ("href", |url| url.href().to_owned()),
+    ("origin", |url| url.origin()),
+    ("protocol", |url| url.protocol().to_owned()),
+    ("username", |url| url.username().to_owned()),
+    ("password", |url| url.password().to_owned()),
+    ("host", |url| url.host().to_owned()),
+    ("hostname", |url| url.hostname().to_owned()),
+    ("port", |url| url.port().to_owned()),
+    ("pathname", |url| url.pathname().to_owned()),
+    ("search", |url| url.search().to_owned()),
+    ("hash", |url| url.hash().to_owned()),
+    ("searchParams", |url| {
+        UrlSearchParams::parse(url.search())
+            .expect("search params should always parse")
+            .to_string()
+    }),
+];
+
+/// Returns a description of every mismatch between the case and the parsed URL.
+fn run_case(case: &serde_json::Map<String, Value>) -> Vec<String> {
+    let input = case["input"].as_str().expect("input should be a string");
+    let base = case["base"].as_str();
+    let result = Url::parse(input, base);
+
+    if case.get("failure").and_then(Value::as_bool) == Some(true) {
+        return match result {
+            Ok(url) => vec![format!("expected failure, got {:?}", url.href())],
+            Err(_) => vec![],
+        };
+    }
+
+    let url = match result {
+        Ok(url) => url,
+        Err(_) => return vec![format!("expected {:?}, got failure", case["href"])],
+    };
+    COMPONENTS
+        .iter()
+        .filter_map(|(name, getter)| {
+            let expected = case.get(*name)?.as_str()?;
+            let actual = getter(&url);
+            (actual != expected).then(|| format!("{name}: expected {expected:?}, got {actual:?}"))
+        })
+        .collect()
+}
+
+#[test]
+fn urltestdata() {
+    let cases: Vec<Value> =
+        serde_json::from_str(URL_TEST_DATA).expect("urltestdata.json should be valid JSON");
+
+    let mut total = 0;
+    let mut failures = Vec::new();
+    // Entries that are plain strings are comments.
+    for case in cases.iter().filter_map(Value::as_object) {
+        total += 1;
+        let mismatches = run_case(case);
+        if !mismatches.is_empty() {
+            failures.push(format!(
+                "input {:?} with base {:?}:\n  {}",
+                case["input"],
+                case["base"],
+                mismatches.join("\n  ")
+            ));
+        }
+    }
+
+    assert!(total > 0, "no test cases found");
+    assert!(
+        failures.is_empty(),
+        "{} of {total} cases failed:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}